@@ -3,8 +3,8 @@
 extern crate test;
 use test::{black_box, Bencher};
 
-use numpy::PyArray;
-use pyo3::Python;
+use numpy::{PyArray, PyReadonlyArray1, PyReadwriteArray1};
+use pyo3::{types::IntoPyDict, PyAny, Python};
 
 #[bench]
 fn initial_shared_borrow(bencher: &mut Bencher) {
@@ -46,3 +46,66 @@ fn exclusive_borrow(bencher: &mut Bencher) {
         });
     });
 }
+
+fn extract_and_release(bencher: &mut Bencher, borrows: usize, extract: impl Fn(&PyAny)) {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 2 * borrows, false);
+        let locals = [("array", array)].into_py_dict(py);
+
+        // All views share the same base array but do not overlap.
+        let view = |idx: usize| {
+            py.eval(&format!("array[{0}:{0} + 1]", 2 * idx), None, Some(locals))
+                .unwrap()
+        };
+
+        let _others = (1..borrows)
+            .map(|idx| view(idx).extract::<PyReadonlyArray1<f64>>().unwrap())
+            .collect::<Vec<_>>();
+
+        let obj = view(0);
+
+        bencher.iter(|| extract(black_box(obj)));
+    });
+}
+
+#[bench]
+fn extract_shared_borrow_single(bencher: &mut Bencher) {
+    extract_and_release(bencher, 1, |obj| {
+        let _shared = obj.extract::<PyReadonlyArray1<f64>>().unwrap();
+    });
+}
+
+#[bench]
+fn extract_shared_borrow_two(bencher: &mut Bencher) {
+    extract_and_release(bencher, 2, |obj| {
+        let _shared = obj.extract::<PyReadonlyArray1<f64>>().unwrap();
+    });
+}
+
+#[bench]
+fn extract_shared_borrow_many(bencher: &mut Bencher) {
+    extract_and_release(bencher, 128, |obj| {
+        let _shared = obj.extract::<PyReadonlyArray1<f64>>().unwrap();
+    });
+}
+
+#[bench]
+fn extract_exclusive_borrow_single(bencher: &mut Bencher) {
+    extract_and_release(bencher, 1, |obj| {
+        let _exclusive = obj.extract::<PyReadwriteArray1<f64>>().unwrap();
+    });
+}
+
+#[bench]
+fn extract_exclusive_borrow_two(bencher: &mut Bencher) {
+    extract_and_release(bencher, 2, |obj| {
+        let _exclusive = obj.extract::<PyReadwriteArray1<f64>>().unwrap();
+    });
+}
+
+#[bench]
+fn extract_exclusive_borrow_many(bencher: &mut Bencher) {
+    extract_and_release(bencher, 128, |obj| {
+        let _exclusive = obj.extract::<PyReadwriteArray1<f64>>().unwrap();
+    });
+}
@@ -0,0 +1,196 @@
+//! Derive macros for the [`numpy`](https://docs.rs/numpy) crate
+//!
+//! This crate is not intended to be used directly. Please enable the `derive` feature
+//! of the `numpy` crate instead which re-exports the macros defined here.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    ext::IdentExt, parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Index,
+    LitStr, Member, Meta, NestedMeta, Result,
+};
+
+/// Implements `numpy::Element` for a `#[repr(C)]` struct by mapping it to a structured type descriptor.
+///
+/// The fields of the struct become the fields of the [structured data type][structured-arrays]
+/// using the same names, offsets and order as chosen by the compiler. Tuple structs are mapped
+/// to NumPy's default field names `f0`, `f1`, etc.
+///
+/// All fields must implement `Element` themselves and the struct must implement `Clone`.
+/// The resulting data type is trivially copyable if all field types are.
+///
+/// Unless the struct is marked as `#[repr(packed)]`, the data type will be an aligned struct,
+/// i.e. Python code needs to pass `align=True` to `numpy.dtype` to produce a compatible layout.
+///
+/// # Example
+///
+/// ```ignore
+/// use numpy::{Element, PyArray1};
+/// use pyo3::Python;
+///
+/// #[derive(Clone, Element)]
+/// #[repr(C)]
+/// struct Record {
+///     x: f64,
+///     y: f64,
+///     id: u32,
+/// }
+///
+/// Python::with_gil(|py| {
+///     let array = py
+///         .eval("__import__('numpy').zeros(3, dtype=[('x', '<f8'), ('y', '<f8'), ('id', '<u4')], align=True)", None, None)
+///         .unwrap()
+///         .downcast::<PyArray1<Record>>()
+///         .unwrap();
+///
+///     assert_eq!(array.readonly().get(0).unwrap().id, 0);
+/// });
+/// ```
+///
+/// [structured-arrays]: https://numpy.org/doc/stable/user/basics.rec.html
+#[proc_macro_derive(Element)]
+pub fn derive_element(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_element(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_element(input: DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "cannot derive `Element` for generic types",
+        ));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(data) => {
+            return Err(Error::new(
+                data.enum_token.span(),
+                "cannot derive `Element` for enums",
+            ))
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "cannot derive `Element` for unions",
+            ))
+        }
+    };
+
+    let align = parse_repr(&input)?;
+
+    let fields = match fields {
+        Fields::Named(fields) => fields
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.clone().unwrap();
+                let name = LitStr::new(&ident.unraw().to_string(), ident.span());
+                (Member::Named(ident), name, &field.ty)
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unnamed(fields) => fields
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let name = LitStr::new(&format!("f{}", idx), field.span());
+                (Member::Unnamed(Index::from(idx)), name, &field.ty)
+            })
+            .collect::<Vec<_>>(),
+        Fields::Unit => Vec::new(),
+    };
+
+    if fields.is_empty() {
+        return Err(Error::new_spanned(
+            ident,
+            "cannot derive `Element` for structs without fields",
+        ));
+    }
+
+    let tys = fields.iter().map(|(_, _, ty)| ty);
+
+    let field_descrs = fields.iter().map(|(member, name, ty)| {
+        quote! {
+            (
+                #name,
+                <#ty as ::numpy::Element>::get_dtype(py),
+                unsafe { ::std::ptr::addr_of!((*base).#member) as usize - base as usize },
+            )
+        }
+    });
+
+    Ok(quote! {
+        unsafe impl ::numpy::Element for #ident {
+            const IS_COPY: bool = true #(&& <#tys as ::numpy::Element>::IS_COPY)*;
+
+            fn get_dtype(py: ::numpy::pyo3::Python) -> &::numpy::PyArrayDescr {
+                static DTYPE: ::numpy::pyo3::once_cell::GILOnceCell<
+                    ::numpy::pyo3::Py<::numpy::PyArrayDescr>,
+                > = ::numpy::pyo3::once_cell::GILOnceCell::new();
+
+                DTYPE
+                    .get_or_init(py, || {
+                        let uninit = ::std::mem::MaybeUninit::<#ident>::uninit();
+                        let base = uninit.as_ptr();
+
+                        let fields = [#(#field_descrs,)*];
+
+                        ::numpy::PyArrayDescr::from_fields(
+                            py,
+                            &fields,
+                            ::std::mem::size_of::<#ident>(),
+                            #align,
+                        )
+                        .expect("Failed to create structured type descriptor")
+                        .into()
+                    })
+                    .as_ref(py)
+            }
+        }
+    })
+}
+
+/// Checks for `#[repr(C)]` and returns whether the struct is aligned, i.e. not `#[repr(packed)]`.
+fn parse_repr(input: &DeriveInput) -> Result<bool> {
+    let mut is_c = false;
+    let mut is_packed = false;
+
+    for attr in &input.attrs {
+        if !attr.path.is_ident("repr") {
+            continue;
+        }
+
+        if let Meta::List(list) = attr.parse_meta()? {
+            for nested in &list.nested {
+                let path = match nested {
+                    NestedMeta::Meta(Meta::Path(path)) => path,
+                    NestedMeta::Meta(Meta::List(list)) => &list.path,
+                    _ => continue,
+                };
+
+                if path.is_ident("C") {
+                    is_c = true;
+                } else if path.is_ident("packed") {
+                    is_packed = true;
+                }
+            }
+        }
+    }
+
+    if !is_c {
+        return Err(Error::new(
+            Span::call_site(),
+            "deriving `Element` requires `#[repr(C)]` to fix the memory layout of the struct",
+        ));
+    }
+
+    Ok(!is_packed)
+}
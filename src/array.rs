@@ -3,34 +3,43 @@
 //! [ndarray]: https://numpy.org/doc/stable/reference/arrays.ndarray.html
 
 use std::{
+    fmt,
     marker::PhantomData,
     mem,
     os::raw::{c_int, c_void},
     ptr, slice,
 };
 
+#[cfg(feature = "slice-view")]
+use ndarray::SliceArg;
 use ndarray::{
-    Array, ArrayBase, ArrayView, ArrayViewMut, Axis, Data, Dim, Dimension, IntoDimension, Ix0, Ix1,
-    Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn, RawArrayView, RawArrayViewMut, RawData, ShapeBuilder,
-    StrideShape,
+    Array, ArrayBase, ArrayView, ArrayViewMut, Axis, Data, Dim, Dimension, ErrorKind,
+    IntoDimension, Ix0, Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn, RawArrayView, RawArrayViewMut,
+    RawData, RemoveAxis, ShapeBuilder, ShapeError, Slice, StrideShape,
 };
 use num_traits::AsPrimitive;
 use pyo3::{
-    ffi, pyobject_native_type_named, types::PyModule, AsPyPointer, FromPyObject, IntoPy, Py, PyAny,
-    PyClassInitializer, PyDowncastError, PyErr, PyNativeType, PyObject, PyResult, PyTypeInfo,
-    Python, ToPyObject,
+    ffi, pyobject_native_type_named,
+    types::{PyModule, PyTuple},
+    AsPyPointer, FromPyObject, IntoPy, IntoPyPointer, Py, PyAny, PyClassInitializer,
+    PyDowncastError, PyErr, PyNativeType, PyObject, PyResult, PyTypeInfo, Python, ToPyObject,
 };
 
-use crate::borrow::{PyReadonlyArray, PyReadwriteArray};
+use crate::borrow::{PyFrozenArray, PyReadonlyArray, PyReadwriteArray};
 use crate::cold;
 use crate::convert::{ArrayExt, IntoPyArray, NpyIndex, ToNpyDims, ToPyArray};
 use crate::dtype::{Element, PyArrayDescr};
 use crate::error::{
-    BorrowError, DimensionalityError, FromVecError, IgnoreError, NotContiguousError, TypeError,
-    DIMENSIONALITY_MISMATCH_ERR, MAX_DIMENSIONALITY_ERR,
+    BorrowError, CastError, DimensionalityError, FromVecError, IgnoreError, NotContiguousError,
+    TypeError, DIMENSIONALITY_MISMATCH_ERR, MAX_DIMENSIONALITY_ERR,
 };
-use crate::npyffi::{self, npy_intp, NPY_ORDER, PY_ARRAY_API};
+use crate::npyffi::{
+    self, npy_intp, NPY_CASTING, NPY_ORDER, NPY_SEARCHSIDE, NPY_SELECTKIND, NPY_SORTKIND,
+    NPY_TYPES, PY_ARRAY_API,
+};
+use crate::reductions::{Accumulate, Average};
 use crate::slice_container::PySliceContainer;
+use crate::untyped_array::PyUntypedArray;
 
 /// A safe, statically-typed wrapper for NumPy's [`ndarray`][ndarray] class.
 ///
@@ -115,6 +124,101 @@ pub type PyArray6<T> = PyArray<T, Ix6>;
 /// Dynamic-dimensional array.
 pub type PyArrayDyn<T> = PyArray<T, IxDyn>;
 
+/// The rules determining which conversions between element types are allowed, cf. [`PyArray::cast_with`].
+///
+/// See also [`numpy.can_cast`][can-cast] and [`NPY_CASTING`][NPY_CASTING].
+///
+/// [can-cast]: https://numpy.org/doc/stable/reference/generated/numpy.can_cast.html
+/// [NPY_CASTING]: https://numpy.org/doc/stable/reference/c-api/array.html#c.NPY_CASTING
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Casting {
+    /// Only allow identical types.
+    No,
+    /// Allow identical types and changes of byte order.
+    Equiv,
+    /// Only allow conversions which preserve all values, e.g. `i32` into `f64`.
+    Safe,
+    /// Allow safe conversions and conversions within the same kind, e.g. `f64` into `f32`.
+    SameKind,
+    /// Allow all conversions, e.g. `f64` into `u8` which will truncate and wrap around.
+    Unsafe,
+}
+
+impl From<Casting> for NPY_CASTING {
+    fn from(casting: Casting) -> Self {
+        match casting {
+            Casting::No => NPY_CASTING::NPY_NO_CASTING,
+            Casting::Equiv => NPY_CASTING::NPY_EQUIV_CASTING,
+            Casting::Safe => NPY_CASTING::NPY_SAFE_CASTING,
+            Casting::SameKind => NPY_CASTING::NPY_SAME_KIND_CASTING,
+            Casting::Unsafe => NPY_CASTING::NPY_UNSAFE_CASTING,
+        }
+    }
+}
+
+impl fmt::Display for Casting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::No => "no",
+            Self::Equiv => "equiv",
+            Self::Safe => "safe",
+            Self::SameKind => "same_kind",
+            Self::Unsafe => "unsafe",
+        })
+    }
+}
+
+/// The sorting algorithms supported by NumPy, cf. [`PyArray::argsort`].
+///
+/// See also [`numpy.sort`][numpy-sort] and [`NPY_SORTKIND`][NPY_SORTKIND].
+///
+/// [numpy-sort]: https://numpy.org/doc/stable/reference/generated/numpy.sort.html
+/// [NPY_SORTKIND]: https://numpy.org/doc/stable/reference/c-api/array.html#c.NPY_SORTKIND
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SortKind {
+    /// Introsort which is fast on average but not stable.
+    QuickSort,
+    /// Heapsort which is not stable.
+    HeapSort,
+    /// A stable sort which NumPy implements as Timsort or radix sort depending on the element type.
+    MergeSort,
+    /// A stable sort, equivalent to [`MergeSort`][Self::MergeSort].
+    Stable,
+}
+
+impl From<SortKind> for NPY_SORTKIND {
+    fn from(kind: SortKind) -> Self {
+        match kind {
+            SortKind::QuickSort => NPY_SORTKIND::NPY_QUICKSORT,
+            SortKind::HeapSort => NPY_SORTKIND::NPY_HEAPSORT,
+            SortKind::MergeSort | SortKind::Stable => NPY_SORTKIND::NPY_MERGESORT,
+        }
+    }
+}
+
+/// Which of multiple suitable indices to return, cf. [`PyArray::searchsorted`].
+///
+/// See also [`numpy.searchsorted`][numpy-searchsorted] and [`NPY_SEARCHSIDE`][NPY_SEARCHSIDE].
+///
+/// [numpy-searchsorted]: https://numpy.org/doc/stable/reference/generated/numpy.searchsorted.html
+/// [NPY_SEARCHSIDE]: https://numpy.org/doc/stable/reference/c-api/array.html#c.NPY_SEARCHSIDE
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SearchSide {
+    /// Return the first suitable index, i.e. `a[i-1] < v <= a[i]`.
+    Left,
+    /// Return the last suitable index, i.e. `a[i-1] <= v < a[i]`.
+    Right,
+}
+
+impl From<SearchSide> for NPY_SEARCHSIDE {
+    fn from(side: SearchSide) -> Self {
+        match side {
+            SearchSide::Left => NPY_SEARCHSIDE::NPY_SEARCHLEFT,
+            SearchSide::Right => NPY_SEARCHSIDE::NPY_SEARCHRIGHT,
+        }
+    }
+}
+
 /// Returns a handle to NumPy's multiarray module.
 pub fn get_array_module(py: Python<'_>) -> PyResult<&PyModule> {
     PyModule::import(py, npyffi::array::MOD_NAME)
@@ -156,6 +260,12 @@ impl<T, D> PyArray<T, D> {
         self.as_ptr() as _
     }
 
+    /// Returns an untyped view of the array, e.g. to pass it to code which dispatches on its [`dtype`][Self::dtype].
+    #[inline]
+    pub fn as_untyped(&self) -> &PyUntypedArray {
+        unsafe { &*(self as *const Self as *const PyUntypedArray) }
+    }
+
     /// Returns the `dtype` of the array.
     ///
     /// See also [`ndarray.dtype`][ndarray-dtype] and [`PyArray_DTYPE`][PyArray_DTYPE].
@@ -176,15 +286,12 @@ impl<T, D> PyArray<T, D> {
     /// [ndarray-dtype]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.dtype.html
     /// [PyArray_DTYPE]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_DTYPE
     pub fn dtype(&self) -> &PyArrayDescr {
-        unsafe {
-            let descr_ptr = (*self.as_array_ptr()).descr;
-            self.py().from_borrowed_ptr(descr_ptr as _)
-        }
+        self.as_untyped().dtype()
     }
 
     #[inline(always)]
     pub(crate) fn check_flags(&self, flags: c_int) -> bool {
-        unsafe { (*self.as_array_ptr()).flags & flags != 0 }
+        self.as_untyped().check_flags(flags)
     }
 
     /// Returns `true` if the internal data of the array is contiguous,
@@ -209,17 +316,17 @@ impl<T, D> PyArray<T, D> {
     /// });
     /// ```
     pub fn is_contiguous(&self) -> bool {
-        self.check_flags(npyffi::NPY_ARRAY_C_CONTIGUOUS | npyffi::NPY_ARRAY_F_CONTIGUOUS)
+        self.as_untyped().is_contiguous()
     }
 
     /// Returns `true` if the internal data of the array is Fortran-style/column-major contiguous.
     pub fn is_fortran_contiguous(&self) -> bool {
-        self.check_flags(npyffi::NPY_ARRAY_F_CONTIGUOUS)
+        self.as_untyped().is_fortran_contiguous()
     }
 
     /// Returns `true` if the internal data of the array is C-style/row-major contiguous.
     pub fn is_c_contiguous(&self) -> bool {
-        self.check_flags(npyffi::NPY_ARRAY_C_CONTIGUOUS)
+        self.as_untyped().is_c_contiguous()
     }
 
     /// Turn `&PyArray<T,D>` into `Py<PyArray<T,D>>`,
@@ -285,7 +392,7 @@ impl<T, D> PyArray<T, D> {
     /// [PyArray_NDIM]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_NDIM
     #[inline]
     pub fn ndim(&self) -> usize {
-        unsafe { (*self.as_array_ptr()).nd as usize }
+        self.as_untyped().ndim()
     }
 
     /// Returns a slice indicating how many bytes to advance when iterating along each axis.
@@ -308,16 +415,7 @@ impl<T, D> PyArray<T, D> {
     /// [PyArray_STRIDES]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_STRIDES
     #[inline]
     pub fn strides(&self) -> &[isize] {
-        let n = self.ndim();
-        if n == 0 {
-            cold();
-            return &[];
-        }
-        let ptr = self.as_array_ptr();
-        unsafe {
-            let p = (*ptr).strides;
-            slice::from_raw_parts(p, n)
-        }
+        self.as_untyped().strides()
     }
 
     /// Returns a slice which contains dimmensions of the array.
@@ -341,26 +439,17 @@ impl<T, D> PyArray<T, D> {
     /// [PyArray_DIMS]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_DIMS
     #[inline]
     pub fn shape(&self) -> &[usize] {
-        let n = self.ndim();
-        if n == 0 {
-            cold();
-            return &[];
-        }
-        let ptr = self.as_array_ptr();
-        unsafe {
-            let p = (*ptr).dimensions as *mut usize;
-            slice::from_raw_parts(p, n)
-        }
+        self.as_untyped().shape()
     }
 
     /// Calculates the total number of elements in the array.
     pub fn len(&self) -> usize {
-        self.shape().iter().product()
+        self.as_untyped().len()
     }
 
     /// Returns `true` if the there are no elements in the array.
     pub fn is_empty(&self) -> bool {
-        self.shape().iter().any(|dim| *dim == 0)
+        self.as_untyped().is_empty()
     }
 
     /// Returns a pointer to the first element of the array.
@@ -401,6 +490,20 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
         Ok(array)
     }
 
+    /// Wraps an owned pointer to an array returned by NumPy or fetches the pending exception if it is null.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or an owned pointer to a NumPy array of element type `T` and dimensionality `D`.
+    pub(crate) unsafe fn from_owned_ptr_or_err<'py>(
+        py: Python<'py>,
+        ptr: *mut ffi::PyObject,
+    ) -> PyResult<&'py Self> {
+        let ob = py.from_owned_ptr_or_err::<PyAny>(ptr)?;
+
+        Ok(&*(ob as *const PyAny as *const Self))
+    }
+
     /// Same as [`shape`][Self::shape], but returns `D` insead of `&[usize]`.
     #[inline(always)]
     pub fn dims(&self) -> D {
@@ -465,16 +568,14 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
         ID: IntoDimension<Dim = D>,
     {
         let dims = dims.into_dimension();
-        let ptr = PY_ARRAY_API.PyArray_NewFromDescr(
+        let ptr = new_from_descr(
             py,
-            PY_ARRAY_API.get_type_object(py, npyffi::NpyTypes::PyArray_Type),
-            T::get_dtype(py).into_dtype_ptr(),
+            T::get_dtype(py),
             dims.ndim_cint(),
             dims.as_dims_ptr(),
             strides as *mut npy_intp, // strides
             ptr::null_mut(),          // data
             flag,                     // flag
-            ptr::null_mut(),          // obj
         );
 
         Self::from_owned_ptr(py, ptr)
@@ -491,16 +592,14 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
         ID: IntoDimension<Dim = D>,
     {
         let dims = dims.into_dimension();
-        let ptr = PY_ARRAY_API.PyArray_NewFromDescr(
+        let ptr = new_from_descr(
             py,
-            PY_ARRAY_API.get_type_object(py, npyffi::NpyTypes::PyArray_Type),
-            T::get_dtype(py).into_dtype_ptr(),
+            T::get_dtype(py),
             dims.ndim_cint(),
             dims.as_dims_ptr(),
             strides as *mut npy_intp,    // strides
             data_ptr as *mut c_void,     // data
             npyffi::NPY_ARRAY_WRITEABLE, // flag
-            ptr::null_mut(),             // obj
         );
 
         PY_ARRAY_API.PyArray_SetBaseObject(
@@ -613,12 +712,14 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
         ID: IntoDimension<Dim = D>,
     {
         let dims = dims.into_dimension();
+        let dtype = T::get_dtype(py);
+        check_element_type(dtype);
         unsafe {
             let ptr = PY_ARRAY_API.PyArray_Zeros(
                 py,
                 dims.ndim_cint(),
                 dims.as_dims_ptr(),
-                T::get_dtype(py).into_dtype_ptr(),
+                dtype.into_dtype_ptr(),
                 if is_fortran { -1 } else { 0 },
             );
             Self::from_owned_ptr(py, ptr)
@@ -893,6 +994,7 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
     }
 
     /// Get an immutable borrow of the NumPy array
+    #[track_caller]
     pub fn try_readonly(&self) -> Result<PyReadonlyArray<'_, T, D>, BorrowError> {
         PyReadonlyArray::try_new(self)
     }
@@ -904,11 +1006,13 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
     /// Panics if the allocation backing the array is currently mutably borrowed.
     ///
     /// For a non-panicking variant, use [`try_readonly`][Self::try_readonly].
+    #[track_caller]
     pub fn readonly(&self) -> PyReadonlyArray<'_, T, D> {
         self.try_readonly().unwrap()
     }
 
     /// Get a mutable borrow of the NumPy array
+    #[track_caller]
     pub fn try_readwrite(&self) -> Result<PyReadwriteArray<'_, T, D>, BorrowError> {
         PyReadwriteArray::try_new(self)
     }
@@ -923,13 +1027,85 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
     /// For a non-panicking variant, use [`try_readwrite`][Self::try_readwrite].
     ///
     /// [flags]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.flags.html
+    #[track_caller]
     pub fn readwrite(&self) -> PyReadwriteArray<'_, T, D> {
         self.try_readwrite().unwrap()
     }
 
-    fn as_view<S: RawData, F>(&self, from_shape_ptr: F) -> ArrayBase<S, D>
+    /// Permanently freeze the NumPy array
+    ///
+    /// This acquires a shared borrow of the array which is never released, clears its `WRITEABLE` flag
+    /// and keeps the array alive indefinitely. The returned [`PyFrozenArray`] provides access to the interior
+    /// of the array without consulting the global borrow flags. Freezing an array which is already frozen succeeds.
+    ///
+    /// Only access from Rust is checked, i.e. Python code can still modify the data through the base object
+    /// or other views of the array, or by setting the `WRITEABLE` flag again.
+    ///
+    /// The array and its borrow are leaked for the remainder of the process, so this should not be called
+    /// on temporary arrays created on each call.
+    #[track_caller]
+    pub fn try_freeze(&self) -> Result<PyFrozenArray<T, D>, BorrowError> {
+        PyFrozenArray::try_new(self)
+    }
+
+    /// Permanently freeze the NumPy array
+    ///
+    /// See [`try_freeze`][Self::try_freeze] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation backing the array is currently mutably borrowed.
+    ///
+    /// For a non-panicking variant, use [`try_freeze`][Self::try_freeze].
+    #[track_caller]
+    pub fn freeze(&self) -> PyFrozenArray<T, D> {
+        self.try_freeze().unwrap()
+    }
+
+    fn as_view<A, S: RawData, F>(&self, from_shape_ptr: F) -> ArrayBase<S, D>
+    where
+        F: FnOnce(StrideShape<D>, *mut A) -> ArrayBase<S, D>,
+    {
+        self.as_view_of(self.shape(), self.strides(), from_shape_ptr)
+    }
+
+    /// Views the trailing axis of length `N` as elements of type `[T; N]`, cf. [`as_array_of`][Self::as_array_of].
+    fn as_subarray_view<const N: usize, S: RawData, F>(
+        &self,
+        from_shape_ptr: F,
+    ) -> Result<ArrayBase<S, D::Smaller>, ShapeError>
+    where
+        D: RemoveAxis,
+        F: FnOnce(StrideShape<D::Smaller>, *mut [T; N]) -> ArrayBase<S, D::Smaller>,
+    {
+        let (&len, shape) = self
+            .shape()
+            .split_last()
+            .ok_or_else(|| ShapeError::from_kind(ErrorKind::IncompatibleShape))?;
+        let (&stride, strides) = self.strides().split_last().unwrap();
+
+        if len != N || N == 0 {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleShape));
+        }
+
+        let size = mem::size_of::<[T; N]>() as isize;
+        if (N != 1 && stride != mem::size_of::<T>() as isize)
+            || strides.iter().any(|stride| stride % size != 0)
+        {
+            return Err(ShapeError::from_kind(ErrorKind::IncompatibleLayout));
+        }
+
+        Ok(self.as_view_of(shape, strides, from_shape_ptr))
+    }
+
+    fn as_view_of<A, E: Dimension, S: RawData, F>(
+        &self,
+        shape: &[usize],
+        strides: &[isize],
+        from_shape_ptr: F,
+    ) -> ArrayBase<S, E>
     where
-        F: FnOnce(StrideShape<D>, *mut T) -> ArrayBase<S, D>,
+        F: FnOnce(StrideShape<E>, *mut A) -> ArrayBase<S, E>,
     {
         fn inner<D: Dimension>(
             shape: &[usize],
@@ -962,12 +1138,8 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
             (shape.strides(new_strides), inverted_axes, data_ptr)
         }
 
-        let (shape, mut inverted_axes, data_ptr) = inner(
-            self.shape(),
-            self.strides(),
-            mem::size_of::<T>(),
-            self.data() as _,
-        );
+        let (shape, mut inverted_axes, data_ptr) =
+            inner(shape, strides, mem::size_of::<A>(), self.data() as _);
 
         let mut array = from_shape_ptr(shape, data_ptr as _);
 
@@ -1003,11 +1175,139 @@ impl<T: Element, D: Dimension> PyArray<T, D> {
         self.as_view(|shape, ptr| ArrayViewMut::from_shape_ptr(shape, ptr))
     }
 
+    /// Returns an [`ArrayView`] of the internal array which views its trailing axis of length `N`
+    /// as elements of type `[T; N]`, e.g. an array of `f32` with shape `(4, 3)` as four elements of type `[f32; 3]`.
+    ///
+    /// Fails if the trailing axis does not have length `N` or is not contiguous
+    /// or if the strides of the other axes are not multiples of the size of `[T; N]`.
+    ///
+    /// See also [`PyReadonlyArray::as_array_of`].
+    ///
+    /// # Safety
+    ///
+    /// The existence of an exclusive reference to the internal data, e.g. `&mut [T]` or `ArrayViewMut`, implies undefined behavior.
+    pub unsafe fn as_array_of<const N: usize>(
+        &self,
+    ) -> Result<ArrayView<'_, [T; N], D::Smaller>, ShapeError>
+    where
+        D: RemoveAxis,
+    {
+        self.as_subarray_view(|shape, ptr| ArrayView::from_shape_ptr(shape, ptr))
+    }
+
+    /// Returns an [`ArrayViewMut`] of the internal array which views its trailing axis of length `N`
+    /// as elements of type `[T; N]`, cf. [`as_array_of`][Self::as_array_of].
+    ///
+    /// See also [`PyReadwriteArray::as_array_mut_of`].
+    ///
+    /// # Safety
+    ///
+    /// The existence of another reference to the internal data, e.g. `&[T]` or `ArrayView`, implies undefined behavior.
+    pub unsafe fn as_array_mut_of<const N: usize>(
+        &self,
+    ) -> Result<ArrayViewMut<'_, [T; N], D::Smaller>, ShapeError>
+    where
+        D: RemoveAxis,
+    {
+        self.as_subarray_view(|shape, ptr| ArrayViewMut::from_shape_ptr(shape, ptr))
+    }
+
     /// Returns the internal array as [`RawArrayView`] enabling element access via raw pointers
     pub fn as_raw_array(&self) -> RawArrayView<T, D> {
         self.as_view(|shape, ptr| unsafe { RawArrayView::from_shape_ptr(shape, ptr) })
     }
 
+    /// Returns a view of the array sliced according to `info`, e.g. as produced by the [`s!`][ndarray::s] macro.
+    ///
+    /// In contrast to slicing the result of [`as_array`][Self::as_array], this creates a NumPy array
+    /// sharing the data of `self` which can be returned to Python without copying.
+    /// Slicing follows the semantics of [`ndarray`], e.g. negative steps reverse the selected range.
+    ///
+    /// See also [`PyReadonlyArray::slice_view`] and [`PyReadwriteArray::slice_view`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is out of bounds or a step size is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "slice-view")] {
+    /// use ndarray::{array, s};
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1, 2, 3], [4, 5, 6]]);
+    ///
+    ///     let view = pyarray.slice_view(s![.., ..;-2]);
+    ///     assert_eq!(view.readonly().as_array(), array![[3, 1], [6, 4]]);
+    ///
+    ///     let row = pyarray.slice_view(s![1, ..]);
+    ///     assert_eq!(row.readonly().as_slice().unwrap(), &[4, 5, 6]);
+    /// });
+    /// # }
+    /// ```
+    #[cfg(feature = "slice-view")]
+    pub fn slice_view<I>(&self, info: I) -> &PyArray<T, I::OutDim>
+    where
+        I: SliceArg<D>,
+    {
+        // Slice a view of the bytes so that the resulting strides are in units of bytes as well.
+        let view = self
+            .as_view(|shape, ptr: *mut u8| unsafe { RawArrayView::from_shape_ptr(shape, ptr) })
+            .slice_move(info);
+
+        unsafe { self.new_view(view.raw_dim(), view.strides(), view.as_ptr()) }
+    }
+
+    /// Returns a view of the array sliced along a single axis.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` or an index is out of bounds or the step size is zero.
+    pub(crate) fn slice_axis_view(&self, axis: Axis, slice: Slice) -> &Self {
+        // Slice a view of the bytes so that the resulting strides are in units of bytes as well.
+        let mut view =
+            self.as_view(|shape, ptr: *mut u8| unsafe { RawArrayView::from_shape_ptr(shape, ptr) });
+        view.slice_axis_inplace(axis, slice);
+
+        unsafe { self.new_view(view.raw_dim(), view.strides(), view.as_ptr()) }
+    }
+
+    /// Creates a NumPy array of the given shape and strides in units of bytes
+    /// which borrows the data of `self` starting at `data_ptr`.
+    ///
+    /// # Safety
+    ///
+    /// All elements addressed by `dims` and `strides` must be within the data of `self`.
+    unsafe fn new_view<E: Dimension>(
+        &self,
+        dims: E,
+        strides: &[isize],
+        data_ptr: *const u8,
+    ) -> &PyArray<T, E> {
+        let py = self.py();
+
+        let mut strides = strides.to_vec();
+
+        let ptr = new_from_descr(
+            py,
+            self.dtype(),
+            dims.ndim_cint(),
+            dims.as_dims_ptr(),
+            strides.as_mut_ptr(),
+            data_ptr as *mut c_void,
+            (*self.as_array_ptr()).flags & npyffi::NPY_ARRAY_WRITEABLE,
+        );
+
+        let array = PyArray::from_owned_ptr(py, ptr);
+
+        PY_ARRAY_API.PyArray_SetBaseObject(py, array.as_array_ptr(), self.to_object(py).into_ptr());
+
+        array
+    }
+
     /// Returns the internal array as [`RawArrayViewMut`] enabling element access via raw pointers
     pub fn as_raw_array_mut(&self) -> RawArrayViewMut<T, D> {
         self.as_view(|shape, ptr| unsafe { RawArrayViewMut::from_shape_ptr(shape, ptr) })
@@ -1312,6 +1612,62 @@ impl<T: Element, D> PyArray<T, D> {
         }
     }
 
+    /// Same as [`copy_to`][Self::copy_to], but fails instead of converting the elements
+    /// if the conversion is not allowed by the given `casting` rule.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{Casting, PyArray};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray_f = PyArray::from_slice(py, &[2.5, 3.5]);
+    ///     let pyarray_u = unsafe { PyArray::<u8, _>::new(py, [2], false) };
+    ///
+    ///     assert!(pyarray_f.copy_to_with(pyarray_u, Casting::Safe).is_err());
+    ///     assert!(pyarray_f.copy_to_with(pyarray_u, Casting::Unsafe).is_ok());
+    ///
+    ///     assert_eq!(pyarray_u.readonly().as_slice().unwrap(), &[2, 3]);
+    /// });
+    /// ```
+    pub fn copy_to_with<U: Element>(
+        &self,
+        other: &PyArray<U, D>,
+        casting: Casting,
+    ) -> PyResult<()> {
+        self.check_cast(other.dtype(), casting)?;
+        self.copy_to(other)
+    }
+
+    /// Returns `true` if the elements of the array can be converted into `U` according to the given `casting` rule.
+    ///
+    /// See also [`PyArray_CanCastArrayTo`][PyArray_CanCastArrayTo].
+    ///
+    /// [PyArray_CanCastArrayTo]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_CanCastArrayTo
+    pub fn can_cast_to<U: Element>(&self, casting: Casting) -> bool {
+        self.can_cast_to_dtype(U::get_dtype(self.py()), casting)
+    }
+
+    fn can_cast_to_dtype(&self, dtype: &PyArrayDescr, casting: Casting) -> bool {
+        unsafe {
+            PY_ARRAY_API.PyArray_CanCastArrayTo(
+                self.py(),
+                self.as_array_ptr(),
+                dtype.as_dtype_ptr(),
+                casting.into(),
+            ) != 0
+        }
+    }
+
+    fn check_cast<'py>(&'py self, dtype: &'py PyArrayDescr, casting: Casting) -> PyResult<()> {
+        if self.can_cast_to_dtype(dtype, casting) {
+            Ok(())
+        } else {
+            Err(CastError::new(self.dtype(), dtype, casting).into())
+        }
+    }
+
     /// Cast the `PyArray<T>` to `PyArray<U>`, by allocating a new array.
     ///
     /// See also [`PyArray_CastToType`][PyArray_CastToType].
@@ -1332,20 +1688,76 @@ impl<T: Element, D> PyArray<T, D> {
     /// ```
     ///
     /// [PyArray_CastToType]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_CastToType
-    pub fn cast<'py, U: Element>(&'py self, is_fortran: bool) -> PyResult<&'py PyArray<U, D>> {
+    pub fn cast<'py, U: Element>(&'py self, is_fortran: bool) -> PyResult<&'py PyArray<U, D>>
+    where
+        D: Dimension,
+    {
+        let dtype = U::get_dtype(self.py());
+        check_element_type(dtype);
+
         let ptr = unsafe {
             PY_ARRAY_API.PyArray_CastToType(
                 self.py(),
                 self.as_array_ptr(),
-                U::get_dtype(self.py()).into_dtype_ptr(),
+                dtype.into_dtype_ptr(),
                 if is_fortran { -1 } else { 0 },
             )
         };
-        if !ptr.is_null() {
-            Ok(unsafe { PyArray::<U, D>::from_owned_ptr(self.py(), ptr) })
-        } else {
-            Err(PyErr::fetch(self.py()))
-        }
+        unsafe { PyArray::<U, D>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Cast the `PyArray<T>` to `PyArray<U>` by allocating a new array with the given memory `order`,
+    /// failing instead of converting the elements if the conversion is not allowed by the given `casting` rule.
+    ///
+    /// Using [`NPY_KEEPORDER`][NPY_ORDER::NPY_KEEPORDER] will match the memory layout of `self` as closely as possible.
+    ///
+    /// See also [`ndarray.astype`][ndarray-astype] and [`PyArray_NewLikeArray`][PyArray_NewLikeArray].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{npyffi::NPY_ORDER, Casting, PyArray};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray_f = PyArray::from_slice(py, &[2.5, 3.5, 4.5]);
+    ///
+    ///     assert!(pyarray_f.cast_with::<u8>(Casting::Safe, NPY_ORDER::NPY_KEEPORDER).is_err());
+    ///
+    ///     let pyarray_f32 = pyarray_f.cast_with::<f32>(Casting::SameKind, NPY_ORDER::NPY_KEEPORDER).unwrap();
+    ///     assert_eq!(pyarray_f32.readonly().as_slice().unwrap(), &[2.5, 3.5, 4.5]);
+    /// });
+    /// ```
+    ///
+    /// [ndarray-astype]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.astype.html
+    /// [PyArray_NewLikeArray]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_NewLikeArray
+    pub fn cast_with<'py, U: Element>(
+        &'py self,
+        casting: Casting,
+        order: NPY_ORDER,
+    ) -> PyResult<&'py PyArray<U, D>>
+    where
+        D: Dimension,
+    {
+        let py = self.py();
+        let dtype = U::get_dtype(py);
+        self.check_cast(dtype, casting)?;
+
+        check_element_type(dtype);
+
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_NewLikeArray(
+                py,
+                self.as_array_ptr(),
+                order,
+                dtype.into_dtype_ptr(),
+                0,
+            )
+        };
+
+        let array = unsafe { PyArray::<U, D>::from_owned_ptr_or_err(py, ptr)? };
+        self.copy_to(array)?;
+        Ok(array)
     }
 
     /// Construct a new array which has same values as self,
@@ -1379,20 +1791,17 @@ impl<T: Element, D> PyArray<T, D> {
         dims: ID,
         order: NPY_ORDER,
     ) -> PyResult<&'py PyArray<T, ID::Dim>> {
-        let mut dims = dims.into_dimension().to_npy_dims();
+        let dims = dims.into_dimension();
+        let mut npy_dims = dims.to_npy_dims();
         let ptr = unsafe {
             PY_ARRAY_API.PyArray_Newshape(
                 self.py(),
                 self.as_array_ptr(),
-                &mut dims as *mut npyffi::PyArray_Dims,
+                &mut npy_dims as *mut npyffi::PyArray_Dims,
                 order,
             )
         };
-        if !ptr.is_null() {
-            Ok(unsafe { PyArray::<T, ID::Dim>::from_owned_ptr(self.py(), ptr) })
-        } else {
-            Err(PyErr::fetch(self.py()))
-        }
+        unsafe { PyArray::<T, ID::Dim>::from_owned_ptr_or_err(self.py(), ptr) }
     }
 
     /// Special case of [`reshape_with_order`][Self::reshape_with_order] which keeps the memory order the same.
@@ -1404,58 +1813,41 @@ impl<T: Element, D> PyArray<T, D> {
         self.reshape_with_order(dims, NPY_ORDER::NPY_ANYORDER)
     }
 
-    /// Extends or truncates the dimensions of an array.
-    ///
-    /// This method works only on [contiguous][`Self::is_contiguous`] arrays.
-    /// Missing elements will be initialized as if calling [`zeros`][Self::zeros].
-    ///
-    /// See also [`ndarray.resize`][ndarray-resize] and [`PyArray_Resize`][PyArray_Resize].
+    /// Returns a view of the array with the order of its axes reversed.
     ///
-    /// # Safety
-    ///
-    /// There should be no outstanding references (shared or exclusive) into the array
-    /// as this method might re-allocate it and thereby invalidate all pointers into it.
+    /// See also [`ndarray.transpose`][ndarray-transpose] and [`PyArray_Transpose`][PyArray_Transpose].
     ///
     /// # Example
     ///
     /// ```
+    /// use ndarray::array;
     /// use numpy::PyArray;
     /// use pyo3::Python;
     ///
     /// Python::with_gil(|py| {
-    ///     let pyarray = PyArray::<f64, _>::zeros(py, (10, 10), false);
-    ///     assert_eq!(pyarray.shape(), [10, 10]);
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1, 2, 3], [4, 5, 6]]);
     ///
-    ///     unsafe {
-    ///         pyarray.resize((100, 100)).unwrap();
-    ///     }
-    ///     assert_eq!(pyarray.shape(), [100, 100]);
+    ///     let transposed = pyarray.transpose().unwrap();
+    ///     assert_eq!(transposed.readonly().as_array(), array![[1, 4], [2, 5], [3, 6]]);
     /// });
     /// ```
     ///
-    /// [ndarray-resize]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.resize.html
-    /// [PyArray_Resize]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Resize
-    pub unsafe fn resize<ID: IntoDimension>(&self, dims: ID) -> PyResult<()> {
-        let mut dims = dims.into_dimension().to_npy_dims();
-        let res = PY_ARRAY_API.PyArray_Resize(
-            self.py(),
-            self.as_array_ptr(),
-            &mut dims as *mut npyffi::PyArray_Dims,
-            1,
-            NPY_ORDER::NPY_ANYORDER,
-        );
-        if !res.is_null() {
-            Ok(())
-        } else {
-            Err(PyErr::fetch(self.py()))
-        }
+    /// [ndarray-transpose]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.transpose.html
+    /// [PyArray_Transpose]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Transpose
+    pub fn transpose<'py>(&'py self) -> PyResult<&'py PyArray<T, D>>
+    where
+        D: Dimension,
+    {
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_Transpose(self.py(), self.as_array_ptr(), ptr::null_mut())
+        };
+        unsafe { PyArray::<T, D>::from_owned_ptr_or_err(self.py(), ptr) }
     }
-}
 
-impl<T: Element + AsPrimitive<f64>> PyArray<T, Ix1> {
-    /// Return evenly spaced values within a given interval.
+    /// Returns a view of the array with its axes permuted such that
+    /// the `i`-th axis of the result is the `axes[i]`-th axis of `self`.
     ///
-    /// See [numpy.arange][numpy.arange] for the Python API and [PyArray_Arange][PyArray_Arange] for the C API.
+    /// Fails if `axes` is not a permutation of the axes of the array.
     ///
     /// # Example
     ///
@@ -1464,11 +1856,959 @@ impl<T: Element + AsPrimitive<f64>> PyArray<T, Ix1> {
     /// use pyo3::Python;
     ///
     /// Python::with_gil(|py| {
-    ///     let pyarray = PyArray::arange(py, 2.0, 4.0, 0.5);
-    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[2.0, 2.5, 3.0, 3.5]);
+    ///     let pyarray = PyArray::<f64, _>::zeros(py, [2, 3, 4], false);
     ///
-    ///     let pyarray = PyArray::arange(py, -2, 4, 3);
-    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[-2, 1]);
+    ///     let permuted = pyarray.permute_axes([1, 2, 0]).unwrap();
+    ///     assert_eq!(permuted.shape(), [3, 4, 2]);
+    ///
+    ///     assert!(pyarray.permute_axes([0, 0, 1]).is_err());
+    /// });
+    /// ```
+    pub fn permute_axes<'py, ID>(&'py self, axes: ID) -> PyResult<&'py PyArray<T, D>>
+    where
+        D: Dimension,
+        ID: IntoDimension<Dim = D>,
+    {
+        // The dimension must outlive `npy_dims` which points into it.
+        let axes = axes.into_dimension();
+        let mut npy_dims = axes.to_npy_dims();
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_Transpose(
+                self.py(),
+                self.as_array_ptr(),
+                &mut npy_dims as *mut npyffi::PyArray_Dims,
+            )
+        };
+        unsafe { PyArray::<T, D>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Returns a view of the array with the axes `a` and `b` interchanged.
+    ///
+    /// See also [`numpy.swapaxes`][numpy-swapaxes] and [`PyArray_SwapAxes`][PyArray_SwapAxes].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::Axis;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::<f64, _>::zeros(py, [2, 3, 4], false);
+    ///
+    ///     let swapped = pyarray.swap_axes(Axis(0), Axis(2)).unwrap();
+    ///     assert_eq!(swapped.shape(), [4, 3, 2]);
+    ///     assert_eq!(swapped.strides(), [8, 32, 96]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-swapaxes]: https://numpy.org/doc/stable/reference/generated/numpy.swapaxes.html
+    /// [PyArray_SwapAxes]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_SwapAxes
+    pub fn swap_axes<'py>(&'py self, a: Axis, b: Axis) -> PyResult<&'py PyArray<T, D>>
+    where
+        D: Dimension,
+    {
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_SwapAxes(
+                self.py(),
+                self.as_array_ptr(),
+                a.index() as c_int,
+                b.index() as c_int,
+            )
+        };
+        unsafe { PyArray::<T, D>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Returns a view of the array with all axes of length one removed.
+    ///
+    /// As the number of removed axes is only known at runtime, the resulting array has dynamic dimensionality.
+    ///
+    /// See also [`numpy.squeeze`][numpy-squeeze] and [`PyArray_Squeeze`][PyArray_Squeeze].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::<f64, _>::zeros(py, [1, 3, 1, 2], false);
+    ///
+    ///     let squeezed = pyarray.squeeze().unwrap();
+    ///     assert_eq!(squeezed.shape(), [3, 2]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-squeeze]: https://numpy.org/doc/stable/reference/generated/numpy.squeeze.html
+    /// [PyArray_Squeeze]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Squeeze
+    pub fn squeeze<'py>(&'py self) -> PyResult<&'py PyArray<T, IxDyn>> {
+        let ptr = unsafe { PY_ARRAY_API.PyArray_Squeeze(self.py(), self.as_array_ptr()) };
+        unsafe { PyArray::<T, IxDyn>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Returns a view of the array with an axis of length one inserted at `axis`.
+    ///
+    /// See also [`numpy.expand_dims`][numpy-expand-dims] and [`ArrayBase::insert_axis`][ndarray::ArrayBase::insert_axis].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is larger than the number of axes of the array.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::Axis;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::<f64, _>::zeros(py, [2, 3], false);
+    ///
+    ///     let expanded = pyarray.expand_dims(Axis(1));
+    ///     assert_eq!(expanded.shape(), [2, 1, 3]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-expand-dims]: https://numpy.org/doc/stable/reference/generated/numpy.expand_dims.html
+    pub fn expand_dims(&self, axis: Axis) -> &PyArray<T, D::Larger>
+    where
+        D: Dimension,
+    {
+        let ndim = self.ndim();
+        assert!(
+            axis.index() <= ndim,
+            "axis {} is out of bounds for array of dimension {}",
+            axis.index(),
+            ndim
+        );
+
+        let mut dims = D::Larger::zeros(ndim + 1);
+        let mut strides = Vec::with_capacity(ndim + 1);
+
+        for (idx, (&len, &stride)) in self.shape().iter().zip(self.strides()).enumerate() {
+            if idx == axis.index() {
+                strides.push(0);
+            }
+            dims[strides.len()] = len;
+            strides.push(stride);
+        }
+        if axis.index() == ndim {
+            strides.push(0);
+        }
+        dims[axis.index()] = 1;
+
+        // SAFETY: The view addresses exactly the same elements as `self`.
+        unsafe { self.new_view(dims, &strides, self.data() as *const u8) }
+    }
+
+    /// Returns a one-dimensional array containing the elements of `self` in the given memory `order`.
+    ///
+    /// A view is returned if possible, otherwise the elements are copied.
+    ///
+    /// See also [`numpy.ravel`][numpy-ravel] and [`PyArray_Ravel`][PyArray_Ravel].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::array;
+    /// use numpy::{npyffi::NPY_ORDER, PyArray};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1, 2], [3, 4]]);
+    ///
+    ///     let raveled = pyarray.ravel(NPY_ORDER::NPY_CORDER).unwrap();
+    ///     assert_eq!(raveled.readonly().as_slice().unwrap(), &[1, 2, 3, 4]);
+    ///
+    ///     let raveled = pyarray.ravel(NPY_ORDER::NPY_FORTRANORDER).unwrap();
+    ///     assert_eq!(raveled.readonly().as_slice().unwrap(), &[1, 3, 2, 4]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-ravel]: https://numpy.org/doc/stable/reference/generated/numpy.ravel.html
+    /// [PyArray_Ravel]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Ravel
+    pub fn ravel<'py>(&'py self, order: NPY_ORDER) -> PyResult<&'py PyArray<T, Ix1>> {
+        let ptr = unsafe { PY_ARRAY_API.PyArray_Ravel(self.py(), self.as_array_ptr(), order) };
+        unsafe { PyArray::<T, Ix1>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Returns a one-dimensional copy of the elements of `self` in the given memory `order`.
+    ///
+    /// In contrast to [`ravel`][Self::ravel], this always copies the elements.
+    ///
+    /// See also [`ndarray.flatten`][ndarray-flatten] and [`PyArray_Flatten`][PyArray_Flatten].
+    ///
+    /// [ndarray-flatten]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.flatten.html
+    /// [PyArray_Flatten]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Flatten
+    pub fn flatten<'py>(&'py self, order: NPY_ORDER) -> PyResult<&'py PyArray<T, Ix1>> {
+        let ptr = unsafe { PY_ARRAY_API.PyArray_Flatten(self.py(), self.as_array_ptr(), order) };
+        unsafe { PyArray::<T, Ix1>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Extends or truncates the dimensions of an array.
+    ///
+    /// This method works only on [contiguous][`Self::is_contiguous`] arrays.
+    /// Missing elements will be initialized as if calling [`zeros`][Self::zeros].
+    ///
+    /// See also [`ndarray.resize`][ndarray-resize] and [`PyArray_Resize`][PyArray_Resize].
+    ///
+    /// # Safety
+    ///
+    /// There should be no outstanding references (shared or exclusive) into the array
+    /// as this method might re-allocate it and thereby invalidate all pointers into it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::<f64, _>::zeros(py, (10, 10), false);
+    ///     assert_eq!(pyarray.shape(), [10, 10]);
+    ///
+    ///     unsafe {
+    ///         pyarray.resize((100, 100)).unwrap();
+    ///     }
+    ///     assert_eq!(pyarray.shape(), [100, 100]);
+    /// });
+    /// ```
+    ///
+    /// [ndarray-resize]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.resize.html
+    /// [PyArray_Resize]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Resize
+    pub unsafe fn resize<ID: IntoDimension>(&self, dims: ID) -> PyResult<()> {
+        let dims = dims.into_dimension();
+        let mut npy_dims = dims.to_npy_dims();
+        let res = PY_ARRAY_API.PyArray_Resize(
+            self.py(),
+            self.as_array_ptr(),
+            &mut npy_dims as *mut npyffi::PyArray_Dims,
+            1,
+            NPY_ORDER::NPY_ANYORDER,
+        );
+        if !res.is_null() {
+            Ok(())
+        } else {
+            Err(PyErr::fetch(self.py()))
+        }
+    }
+}
+
+impl<T: Element, D: Dimension> PyArray<T, D> {
+    /// Returns the sum of all elements of the array.
+    ///
+    /// The sum is accumulated in the [promoted type][Accumulate], e.g. `i64` for `i32` elements.
+    ///
+    /// See also [`numpy.sum`][numpy-sum] and [`PyArray_Sum`][PyArray_Sum].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::array;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1_i32, 2], [3, 4]]);
+    ///
+    ///     let sum: i64 = pyarray.sum().unwrap();
+    ///     assert_eq!(sum, 10);
+    /// });
+    /// ```
+    ///
+    /// [numpy-sum]: https://numpy.org/doc/stable/reference/generated/numpy.sum.html
+    /// [PyArray_Sum]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Sum
+    pub fn sum(&self) -> PyResult<T::Output>
+    where
+        T: Accumulate,
+    {
+        self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_Sum(
+                self.py(),
+                self.as_array_ptr(),
+                npyffi::NPY_MAXDIMS,
+                T::Output::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the sums of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::{array, Axis};
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1_u8, 2], [3, 4]]);
+    ///
+    ///     let sums = pyarray.sum_axis(Axis(1)).unwrap();
+    ///     assert_eq!(sums.readonly().as_slice().unwrap(), [3_u64, 7]);
+    /// });
+    /// ```
+    pub fn sum_axis(&self, axis: Axis) -> PyResult<&PyArray<T::Output, D::Smaller>>
+    where
+        D: RemoveAxis,
+        T: Accumulate,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_Sum(
+                self.py(),
+                self.as_array_ptr(),
+                axis.index() as c_int,
+                T::Output::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the product of all elements of the array.
+    ///
+    /// The product is accumulated in the [promoted type][Accumulate], e.g. `i64` for `i32` elements.
+    ///
+    /// See also [`numpy.prod`][numpy-prod] and [`PyArray_Prod`][PyArray_Prod].
+    ///
+    /// [numpy-prod]: https://numpy.org/doc/stable/reference/generated/numpy.prod.html
+    /// [PyArray_Prod]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Prod
+    pub fn prod(&self) -> PyResult<T::Output>
+    where
+        T: Accumulate,
+    {
+        self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_Prod(
+                self.py(),
+                self.as_array_ptr(),
+                npyffi::NPY_MAXDIMS,
+                T::Output::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the products of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn prod_axis(&self, axis: Axis) -> PyResult<&PyArray<T::Output, D::Smaller>>
+    where
+        D: RemoveAxis,
+        T: Accumulate,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_Prod(
+                self.py(),
+                self.as_array_ptr(),
+                axis.index() as c_int,
+                T::Output::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the arithmetic mean of all elements of the array.
+    ///
+    /// Integers are averaged as `f64`, cf. [`Average`].
+    ///
+    /// See also [`numpy.mean`][numpy-mean] and [`PyArray_Mean`][PyArray_Mean].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_vec(py, vec![1_i32, 2, 3, 4]);
+    ///
+    ///     let mean: f64 = pyarray.mean().unwrap();
+    ///     assert_eq!(mean, 2.5);
+    /// });
+    /// ```
+    ///
+    /// [numpy-mean]: https://numpy.org/doc/stable/reference/generated/numpy.mean.html
+    /// [PyArray_Mean]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Mean
+    pub fn mean(&self) -> PyResult<T::Mean>
+    where
+        T: Average,
+    {
+        self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_Mean(
+                self.py(),
+                self.as_array_ptr(),
+                npyffi::NPY_MAXDIMS,
+                T::Mean::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the arithmetic means of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn mean_axis(&self, axis: Axis) -> PyResult<&PyArray<T::Mean, D::Smaller>>
+    where
+        D: RemoveAxis,
+        T: Average,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_Mean(
+                self.py(),
+                self.as_array_ptr(),
+                axis.index() as c_int,
+                T::Mean::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the population standard deviation of all elements of the array.
+    ///
+    /// Integers are averaged as `f64` and the standard deviation of complex numbers is real, cf. [`Average`].
+    ///
+    /// See also [`numpy.std`][numpy-std] and [`PyArray_Std`][PyArray_Std].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_vec(py, vec![2_i32, 4, 4, 4, 5, 5, 7, 9]);
+    ///
+    ///     let std: f64 = pyarray.std().unwrap();
+    ///     assert_eq!(std, 2.0);
+    /// });
+    /// ```
+    ///
+    /// [numpy-std]: https://numpy.org/doc/stable/reference/generated/numpy.std.html
+    /// [PyArray_Std]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Std
+    pub fn std(&self) -> PyResult<T::Deviation>
+    where
+        T: Average,
+    {
+        self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_Std(
+                self.py(),
+                self.as_array_ptr(),
+                npyffi::NPY_MAXDIMS,
+                self.std_rtype(),
+                out,
+                0,
+            )
+        })
+    }
+
+    /// Returns the population standard deviations of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn std_axis(&self, axis: Axis) -> PyResult<&PyArray<T::Deviation, D::Smaller>>
+    where
+        D: RemoveAxis,
+        T: Average,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_Std(
+                self.py(),
+                self.as_array_ptr(),
+                axis.index() as c_int,
+                self.std_rtype(),
+                out,
+                0,
+            )
+        })
+    }
+
+    /// Returns the minimum of all elements of the array.
+    ///
+    /// Fails if the array is empty.
+    ///
+    /// See also [`numpy.amin`][numpy-amin] and [`PyArray_Min`][PyArray_Min].
+    ///
+    /// [numpy-amin]: https://numpy.org/doc/stable/reference/generated/numpy.amin.html
+    /// [PyArray_Min]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Min
+    pub fn min(&self) -> PyResult<T> {
+        self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_Min(self.py(), self.as_array_ptr(), npyffi::NPY_MAXDIMS, out)
+        })
+    }
+
+    /// Returns the minima of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn min_axis(&self, axis: Axis) -> PyResult<&PyArray<T, D::Smaller>>
+    where
+        D: RemoveAxis,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_Min(self.py(), self.as_array_ptr(), axis.index() as c_int, out)
+        })
+    }
+
+    /// Returns the maximum of all elements of the array.
+    ///
+    /// Fails if the array is empty.
+    ///
+    /// See also [`numpy.amax`][numpy-amax] and [`PyArray_Max`][PyArray_Max].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::{array, Axis};
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1.0, 5.0], [3.0, 2.0]]);
+    ///
+    ///     assert_eq!(pyarray.max().unwrap(), 5.0);
+    ///
+    ///     let maxima = pyarray.max_axis(Axis(0)).unwrap();
+    ///     assert_eq!(maxima.readonly().as_slice().unwrap(), [3.0, 5.0]);
+    ///
+    ///     assert!(PyArray::<f64, _>::zeros(py, 0, false).max().is_err());
+    /// });
+    /// ```
+    ///
+    /// [numpy-amax]: https://numpy.org/doc/stable/reference/generated/numpy.amax.html
+    /// [PyArray_Max]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Max
+    pub fn max(&self) -> PyResult<T> {
+        self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_Max(self.py(), self.as_array_ptr(), npyffi::NPY_MAXDIMS, out)
+        })
+    }
+
+    /// Returns the maxima of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn max_axis(&self, axis: Axis) -> PyResult<&PyArray<T, D::Smaller>>
+    where
+        D: RemoveAxis,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_Max(self.py(), self.as_array_ptr(), axis.index() as c_int, out)
+        })
+    }
+
+    /// Returns the index of the first maximum of the array when it is flattened in row-major order.
+    ///
+    /// Fails if the array is empty.
+    ///
+    /// See also [`numpy.argmax`][numpy-argmax] and [`PyArray_ArgMax`][PyArray_ArgMax].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::{array, Axis};
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1, 5], [3, 2]]);
+    ///
+    ///     assert_eq!(pyarray.argmax().unwrap(), 1);
+    ///
+    ///     let indices = pyarray.argmax_axis(Axis(0)).unwrap();
+    ///     assert_eq!(indices.readonly().as_slice().unwrap(), [1, 0]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-argmax]: https://numpy.org/doc/stable/reference/generated/numpy.argmax.html
+    /// [PyArray_ArgMax]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_ArgMax
+    pub fn argmax(&self) -> PyResult<usize> {
+        let index: isize = self.reduce_to_scalar(|out| unsafe {
+            PY_ARRAY_API.PyArray_ArgMax(self.py(), self.as_array_ptr(), npyffi::NPY_MAXDIMS, out)
+        })?;
+        Ok(index as usize)
+    }
+
+    /// Returns the indices of the first maxima of the array along the given `axis`.
+    ///
+    /// The indices have type `isize` which corresponds to NumPy's `intp`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn argmax_axis(&self, axis: Axis) -> PyResult<&PyArray<isize, D::Smaller>>
+    where
+        D: RemoveAxis,
+    {
+        self.reduce_into(self.dims_without(axis), |out| unsafe {
+            PY_ARRAY_API.PyArray_ArgMax(self.py(), self.as_array_ptr(), axis.index() as c_int, out)
+        })
+    }
+
+    /// Returns the cumulative sum of the elements of the array when it is flattened in row-major order.
+    ///
+    /// The sums are accumulated in the [promoted type][Accumulate], e.g. `i64` for `i32` elements.
+    ///
+    /// See also [`numpy.cumsum`][numpy-cumsum] and [`PyArray_CumSum`][PyArray_CumSum].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::{array, Axis};
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1_i32, 2], [3, 4]]);
+    ///
+    ///     let sums = pyarray.cumsum().unwrap();
+    ///     assert_eq!(sums.readonly().as_slice().unwrap(), [1_i64, 3, 6, 10]);
+    ///
+    ///     let sums = pyarray.cumsum_axis(Axis(0)).unwrap();
+    ///     assert_eq!(sums.readonly().as_array(), array![[1_i64, 2], [4, 6]]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-cumsum]: https://numpy.org/doc/stable/reference/generated/numpy.cumsum.html
+    /// [PyArray_CumSum]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_CumSum
+    pub fn cumsum(&self) -> PyResult<&PyArray<T::Output, Ix1>>
+    where
+        T: Accumulate,
+    {
+        self.reduce_into(Dim(self.len()), |out| unsafe {
+            PY_ARRAY_API.PyArray_CumSum(
+                self.py(),
+                self.as_array_ptr(),
+                npyffi::NPY_MAXDIMS,
+                T::Output::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    /// Returns the cumulative sums of the elements of the array along the given `axis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds.
+    pub fn cumsum_axis(&self, axis: Axis) -> PyResult<&PyArray<T::Output, D>>
+    where
+        T: Accumulate,
+    {
+        self.check_axis(axis);
+
+        self.reduce_into(self.dims(), |out| unsafe {
+            PY_ARRAY_API.PyArray_CumSum(
+                self.py(),
+                self.as_array_ptr(),
+                axis.index() as c_int,
+                T::Output::get_dtype(self.py()).num(),
+                out,
+            )
+        })
+    }
+
+    pub(crate) fn check_axis(&self, axis: Axis) {
+        let ndim = self.ndim();
+        assert!(
+            axis.index() < ndim,
+            "axis {} is out of bounds for array of dimension {}",
+            axis.index(),
+            ndim
+        );
+    }
+
+    pub(crate) fn dims_without(&self, axis: Axis) -> D::Smaller
+    where
+        D: RemoveAxis,
+    {
+        self.check_axis(axis);
+
+        self.dims().remove_axis(axis)
+    }
+
+    /// Returns the type number passed as `rtype` to `PyArray_Std`.
+    ///
+    /// NumPy uses `rtype` for both the mean and the sum of the squared deviations.
+    /// For complex numbers, the former is complex whereas the latter is real,
+    /// so we let NumPy pick the types which keep the precision of the elements.
+    fn std_rtype(&self) -> c_int
+    where
+        T: Average,
+    {
+        let py = self.py();
+        let mean = T::Mean::get_dtype(py);
+        if mean.is_equiv_to(T::Deviation::get_dtype(py)) {
+            mean.num()
+        } else {
+            NPY_TYPES::NPY_NOTYPE as c_int
+        }
+    }
+
+    /// Allocates an array of shape `dims` and passes it as the `out` argument to `reduce`.
+    fn reduce_into<U, E, F>(&self, dims: E, reduce: F) -> PyResult<&PyArray<U, E>>
+    where
+        U: Element,
+        E: Dimension,
+        F: FnOnce(*mut npyffi::PyArrayObject) -> *mut ffi::PyObject,
+    {
+        let py = self.py();
+        // SAFETY: The reduction will overwrite all elements of `out`.
+        let out = unsafe { PyArray::<U, E>::new(py, dims, false) };
+        // The C-API returns a new reference to `out` if it succeeds.
+        unsafe { py.from_owned_ptr_or_err::<PyAny>(reduce(out.as_array_ptr()))? };
+        Ok(out)
+    }
+
+    fn reduce_to_scalar<U, F>(&self, reduce: F) -> PyResult<U>
+    where
+        U: Element,
+        F: FnOnce(*mut npyffi::PyArrayObject) -> *mut ffi::PyObject,
+    {
+        let out = self.reduce_into::<U, _, _>(Ix0(), reduce)?;
+        Ok(unsafe { (*out.data()).clone() })
+    }
+}
+
+impl<T: Element, D: Dimension> PyArray<T, D> {
+    /// Sorts the elements of the array along the given `axis` in place.
+    ///
+    /// See also [`ndarray.sort`][ndarray-sort] and [`PyArray_Sort`][PyArray_Sort].
+    ///
+    /// # Safety
+    ///
+    /// There must be no other references to the interior of the array.
+    /// Use [`PyReadwriteArray::sort`] to have this checked dynamically.
+    ///
+    /// [ndarray-sort]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.sort.html
+    /// [PyArray_Sort]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Sort
+    pub unsafe fn sort(&self, axis: Axis, kind: SortKind) -> PyResult<()> {
+        let res = PY_ARRAY_API.PyArray_Sort(
+            self.py(),
+            self.as_array_ptr(),
+            axis.index() as c_int,
+            kind.into(),
+        );
+        if res != -1 {
+            Ok(())
+        } else {
+            Err(PyErr::fetch(self.py()))
+        }
+    }
+
+    /// Partially sorts the elements of the array along the given `axis` in place
+    /// such that the elements at the indices `kth` are in their sorted positions,
+    /// all smaller elements are moved before and all other elements after them.
+    ///
+    /// See also [`ndarray.partition`][ndarray-partition] and [`PyArray_Partition`][PyArray_Partition].
+    ///
+    /// # Safety
+    ///
+    /// There must be no other references to the interior of the array.
+    /// Use [`PyReadwriteArray::partition`] to have this checked dynamically.
+    ///
+    /// [ndarray-partition]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.partition.html
+    /// [PyArray_Partition]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_Partition
+    pub unsafe fn partition(&self, kth: &[usize], axis: Axis) -> PyResult<()> {
+        let kth = kth_array(self.py(), kth);
+
+        let res = PY_ARRAY_API.PyArray_Partition(
+            self.py(),
+            self.as_array_ptr(),
+            kth.as_array_ptr(),
+            axis.index() as c_int,
+            NPY_SELECTKIND::NPY_INTROSELECT,
+        );
+        if res != -1 {
+            Ok(())
+        } else {
+            Err(PyErr::fetch(self.py()))
+        }
+    }
+
+    /// Returns the indices which would sort the array along the given `axis`.
+    ///
+    /// See also [`numpy.argsort`][numpy-argsort] and [`PyArray_ArgSort`][PyArray_ArgSort].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::{array, Axis};
+    /// use numpy::{PyArray, SortKind};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[3, 1, 2], [1, 1, 0]]);
+    ///
+    ///     let indices = pyarray.argsort(Axis(1), SortKind::Stable).unwrap();
+    ///     assert_eq!(indices.readonly().as_array(), array![[1, 2, 0], [2, 0, 1]]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-argsort]: https://numpy.org/doc/stable/reference/generated/numpy.argsort.html
+    /// [PyArray_ArgSort]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_ArgSort
+    pub fn argsort(&self, axis: Axis, kind: SortKind) -> PyResult<&PyArray<isize, D>> {
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_ArgSort(
+                self.py(),
+                self.as_array_ptr(),
+                axis.index() as c_int,
+                kind.into(),
+            )
+        };
+        unsafe { PyArray::<isize, D>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Returns the indices which would partition the array along the given `axis`, cf. [`partition`][Self::partition].
+    ///
+    /// See also [`numpy.argpartition`][numpy-argpartition] and [`PyArray_ArgPartition`][PyArray_ArgPartition].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::Axis;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_slice(py, &[5, 1, 4, 2, 3]);
+    ///
+    ///     let indices = pyarray.argpartition(&[2], Axis(0)).unwrap();
+    ///     assert_eq!(indices.get_owned(2), Some(4));
+    /// });
+    /// ```
+    ///
+    /// [numpy-argpartition]: https://numpy.org/doc/stable/reference/generated/numpy.argpartition.html
+    /// [PyArray_ArgPartition]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_ArgPartition
+    pub fn argpartition(&self, kth: &[usize], axis: Axis) -> PyResult<&PyArray<isize, D>> {
+        let kth = kth_array(self.py(), kth);
+
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_ArgPartition(
+                self.py(),
+                self.as_array_ptr(),
+                kth.as_array_ptr(),
+                axis.index() as c_int,
+                NPY_SELECTKIND::NPY_INTROSELECT,
+            )
+        };
+        unsafe { PyArray::<isize, D>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+
+    /// Returns the indices which sort the given `keys` lexicographically along the given `axis`.
+    ///
+    /// The last key is the primary sort key, the second to last key the secondary one and so on.
+    /// All keys must have the same shape.
+    ///
+    /// See also [`numpy.lexsort`][numpy-lexsort] and [`PyArray_LexSort`][PyArray_LexSort].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::Axis;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let first_names = PyArray::from_slice(py, &[3, 1, 2, 1]);
+    ///     let last_names = PyArray::from_slice(py, &[1, 2, 1, 1]);
+    ///
+    ///     let indices = PyArray::lexsort(py, &[first_names, last_names], Axis(0)).unwrap();
+    ///     assert_eq!(indices.readonly().as_slice().unwrap(), [3, 2, 0, 1]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-lexsort]: https://numpy.org/doc/stable/reference/generated/numpy.lexsort.html
+    /// [PyArray_LexSort]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_LexSort
+    pub fn lexsort<'py>(
+        py: Python<'py>,
+        keys: &[&'py Self],
+        axis: Axis,
+    ) -> PyResult<&'py PyArray<isize, D>> {
+        let keys = PyTuple::new(py, keys);
+
+        let ptr = unsafe { PY_ARRAY_API.PyArray_LexSort(py, keys.as_ptr(), axis.index() as c_int) };
+        unsafe { PyArray::<isize, D>::from_owned_ptr_or_err(py, ptr) }
+    }
+}
+
+impl<T: Element> PyArray<T, Ix1> {
+    /// Returns the indices at which the given `values` would need to be inserted
+    /// into this sorted array to maintain its order.
+    ///
+    /// See also [`numpy.searchsorted`][numpy-searchsorted] and [`PyArray_SearchSorted`][PyArray_SearchSorted].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{PyArray, SearchSide};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_slice(py, &[1.0, 2.0, 2.0, 3.0]);
+    ///     let values = PyArray::from_slice(py, &[0.5, 2.0, 4.0]);
+    ///
+    ///     let indices = pyarray.searchsorted(values, SearchSide::Left).unwrap();
+    ///     assert_eq!(indices.readonly().as_slice().unwrap(), [0, 1, 4]);
+    ///
+    ///     let indices = pyarray.searchsorted(values, SearchSide::Right).unwrap();
+    ///     assert_eq!(indices.readonly().as_slice().unwrap(), [0, 3, 4]);
+    /// });
+    /// ```
+    ///
+    /// [numpy-searchsorted]: https://numpy.org/doc/stable/reference/generated/numpy.searchsorted.html
+    /// [PyArray_SearchSorted]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_SearchSorted
+    pub fn searchsorted<'py, E>(
+        &'py self,
+        values: &PyArray<T, E>,
+        side: SearchSide,
+    ) -> PyResult<&'py PyArray<isize, E>>
+    where
+        E: Dimension,
+    {
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_SearchSorted(
+                self.py(),
+                self.as_array_ptr(),
+                values.as_ptr(),
+                side.into(),
+                ptr::null_mut(),
+            )
+        };
+        unsafe { PyArray::<isize, E>::from_owned_ptr_or_err(self.py(), ptr) }
+    }
+}
+
+fn kth_array<'py>(py: Python<'py>, kth: &[usize]) -> &'py PyArray<isize, Ix1> {
+    PyArray::from_iter(py, kth.iter().map(|&kth| kth as isize))
+}
+
+impl<T: Element + AsPrimitive<f64>> PyArray<T, Ix1> {
+    /// Return evenly spaced values within a given interval.
+    ///
+    /// See [numpy.arange][numpy.arange] for the Python API and [PyArray_Arange][PyArray_Arange] for the C API.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 2.0, 4.0, 0.5);
+    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[2.0, 2.5, 3.0, 3.5]);
+    ///
+    ///     let pyarray = PyArray::arange(py, -2, 4, 3);
+    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[-2, 1]);
     /// });
     /// ```
     ///
@@ -1488,6 +2828,43 @@ impl<T: Element + AsPrimitive<f64>> PyArray<T, Ix1> {
     }
 }
 
+/// Creates a new array using [`PyArray_NewFromDescr`][PyArray_NewFromDescr].
+///
+/// [PyArray_NewFromDescr]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_NewFromDescr
+unsafe fn new_from_descr(
+    py: Python,
+    dtype: &PyArrayDescr,
+    nd: c_int,
+    dims: *mut npy_intp,
+    strides: *mut npy_intp,
+    data: *mut c_void,
+    flags: c_int,
+) -> *mut ffi::PyObject {
+    check_element_type(dtype);
+
+    PY_ARRAY_API.PyArray_NewFromDescr(
+        py,
+        PY_ARRAY_API.get_type_object(py, npyffi::NpyTypes::PyArray_Type),
+        dtype.into_dtype_ptr(),
+        nd,
+        dims,
+        strides,
+        data,
+        flags,
+        ptr::null_mut(),
+    )
+}
+
+/// NumPy absorbs sub-array types into the shape of new arrays, e.g. an array of `[f32; 3]`
+/// would become an array of `f32` with an additional trailing axis of length 3,
+/// so that these arrays would not match their element type, cf. [`Element`].
+fn check_element_type(dtype: &PyArrayDescr) {
+    assert!(
+        !dtype.has_subarray(),
+        "Arrays of sub-array element types like `[T; N]` are not supported"
+    );
+}
+
 unsafe fn clone_elements<T: Element>(elems: &[T], data_ptr: &mut *mut T) {
     if T::IS_COPY {
         ptr::copy_nonoverlapping(elems.as_ptr(), *data_ptr, elems.len());
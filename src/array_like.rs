@@ -0,0 +1,364 @@
+//! Extraction of arrays from arbitrary array-like Python objects
+
+use std::any::type_name;
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::ops::Deref;
+use std::panic::Location;
+use std::ptr;
+
+use ndarray::{ArrayViewMut, Dim, Dimension, Ix0, Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn};
+use pyo3::{ffi, AsPyPointer, FromPyObject, PyAny, PyErr, PyResult};
+
+use crate::array::{Casting, PyArray};
+use crate::borrow::{acquire_mut, release_mut, PyReadonlyArray, PyReadwriteArray};
+use crate::convert::NpyIndex;
+use crate::dtype::Element;
+use crate::error::{BorrowError, CastError, DimensionalityError, NotContiguousError};
+use crate::npyffi::{NPY_ARRAY_WRITEABLE, PY_ARRAY_API};
+use crate::untyped_array::PyUntypedArray;
+
+/// Read-only borrow of an array extracted from an arbitrary array-like object.
+///
+/// In contrast to [`PyReadonlyArray`], extracting this type does not fail if the given object
+/// is not a NumPy array of element type `T`. Instead, it will be converted using
+/// [`PyArray_FromAny`][PyArray_FromAny] which accepts lists, tuples, scalars and arrays
+/// of other element types and only copies the data if a conversion is actually necessary.
+///
+/// Type conversions of existing arrays are limited to [safe casts][can-cast], e.g. `int32` into `float64`
+/// but not `float64` into `int32`. The dimensionality of the converted array must match `D`.
+///
+/// # Example
+///
+/// ```
+/// use numpy::PyArrayLike1;
+/// use pyo3::Python;
+///
+/// Python::with_gil(|py| {
+///     let list = py.eval("[1, 2, 3]", None, None).unwrap();
+///     let array: PyArrayLike1<f64> = list.extract().unwrap();
+///     assert_eq!(array.as_slice().unwrap(), [1.0, 2.0, 3.0]);
+///
+///     let array = py.eval("__import__('numpy').arange(3, dtype='int32')", None, None).unwrap();
+///     let array: PyArrayLike1<f64> = array.extract().unwrap();
+///     assert_eq!(array.as_slice().unwrap(), [0.0, 1.0, 2.0]);
+///
+///     let nested = py.eval("[[1, 2], [3, 4]]", None, None).unwrap();
+///     assert!(nested.extract::<PyArrayLike1<f64>>().is_err());
+/// });
+/// ```
+///
+/// [PyArray_FromAny]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_FromAny
+/// [can-cast]: https://numpy.org/doc/stable/reference/generated/numpy.can_cast.html
+#[derive(Debug)]
+pub struct PyArrayLike<'py, T, D>(PyReadonlyArray<'py, T, D>)
+where
+    T: Element,
+    D: Dimension;
+
+/// Read-only borrow of a zero-dimensional array extracted from an array-like object.
+pub type PyArrayLike0<'py, T> = PyArrayLike<'py, T, Ix0>;
+
+/// Read-only borrow of a one-dimensional array extracted from an array-like object.
+pub type PyArrayLike1<'py, T> = PyArrayLike<'py, T, Ix1>;
+
+/// Read-only borrow of a two-dimensional array extracted from an array-like object.
+pub type PyArrayLike2<'py, T> = PyArrayLike<'py, T, Ix2>;
+
+/// Read-only borrow of a three-dimensional array extracted from an array-like object.
+pub type PyArrayLike3<'py, T> = PyArrayLike<'py, T, Ix3>;
+
+/// Read-only borrow of a four-dimensional array extracted from an array-like object.
+pub type PyArrayLike4<'py, T> = PyArrayLike<'py, T, Ix4>;
+
+/// Read-only borrow of a five-dimensional array extracted from an array-like object.
+pub type PyArrayLike5<'py, T> = PyArrayLike<'py, T, Ix5>;
+
+/// Read-only borrow of a six-dimensional array extracted from an array-like object.
+pub type PyArrayLike6<'py, T> = PyArrayLike<'py, T, Ix6>;
+
+/// Read-only borrow of an array whose dimensionality is determined at runtime extracted from an array-like object.
+pub type PyArrayLikeDyn<'py, T> = PyArrayLike<'py, T, IxDyn>;
+
+impl<'py, T, D> Deref for PyArrayLike<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    type Target = PyReadonlyArray<'py, T, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<'py, T, D> PyArrayLike<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    /// Unwraps the underlying read-only borrow.
+    pub fn into_inner(self) -> PyReadonlyArray<'py, T, D> {
+        self.0
+    }
+}
+
+impl<'py, T: Element, D: Dimension> FromPyObject<'py> for PyArrayLike<'py, T, D> {
+    fn extract(ob: &'py PyAny) -> PyResult<Self> {
+        if let Ok(array) = ob.downcast::<PyArray<T, D>>() {
+            return Ok(Self(array.readonly()));
+        }
+
+        let py = ob.py();
+
+        let ptr = unsafe {
+            PY_ARRAY_API.PyArray_FromAny(
+                py,
+                ob.as_ptr(),
+                T::get_dtype(py).into_dtype_ptr(),
+                0,
+                0,
+                0,
+                ptr::null_mut(),
+            )
+        };
+
+        let array: &'py PyArray<T, D> =
+            unsafe { py.from_owned_ptr_or_err::<PyAny>(ptr)? }.extract()?;
+
+        Ok(Self(array.readonly()))
+    }
+}
+
+/// Read-write borrow of an array extracted from an arbitrary NumPy array which is written back when dropped.
+///
+/// In contrast to [`PyReadwriteArray`], extracting this type does not fail if the given array
+/// has an element type other than `T` or is not contiguous. Instead, its elements are copied into a
+/// C-contiguous temporary array of element type `T` which is registered as a [writeback-if-copy][writeback] array,
+/// i.e. the given array is made read-only until the borrow is dropped and the contents of the temporary
+/// are written back by [`PyArray_ResolveWritebackIfCopy`][resolve].
+///
+/// The given array is exclusively borrowed for as long as this borrow is active.
+/// Conversions are limited to casts [within the same kind][can-cast] in both directions,
+/// e.g. between `float32` and `float64` but not between `int64` and `float64`.
+/// The dimensionality of the given array must match `D`.
+///
+/// # Example
+///
+/// ```
+/// use numpy::PyWritebackArray1;
+/// use pyo3::{py_run, types::IntoPyDict, Python};
+///
+/// Python::with_gil(|py| {
+///     let locals = [("np", numpy::get_array_module(py).unwrap())].into_py_dict(py);
+///
+///     let array = py.eval("np.arange(6, dtype='float32')[::2]", None, Some(locals)).unwrap();
+///
+///     {
+///         let mut array: PyWritebackArray1<f64> = array.extract().unwrap();
+///         array.as_slice_mut().unwrap().iter_mut().for_each(|x| *x = x.sqrt() * 0.5);
+///     }
+///
+///     py_run!(py, array, "assert array.tolist() == [0.0, 0.7071067690849304, 1.0]");
+/// });
+/// ```
+///
+/// [writeback]: https://numpy.org/doc/stable/reference/c-api/array.html#c.NPY_ARRAY_WRITEBACKIFCOPY
+/// [resolve]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_ResolveWritebackIfCopy
+/// [can-cast]: https://numpy.org/doc/stable/reference/generated/numpy.can_cast.html
+pub struct PyWritebackArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    array: ManuallyDrop<PyReadwriteArray<'py, T, D>>,
+    original: Option<&'py PyUntypedArray>,
+}
+
+/// Read-write borrow of a zero-dimensional array which is written back when dropped.
+pub type PyWritebackArray0<'py, T> = PyWritebackArray<'py, T, Ix0>;
+
+/// Read-write borrow of a one-dimensional array which is written back when dropped.
+pub type PyWritebackArray1<'py, T> = PyWritebackArray<'py, T, Ix1>;
+
+/// Read-write borrow of a two-dimensional array which is written back when dropped.
+pub type PyWritebackArray2<'py, T> = PyWritebackArray<'py, T, Ix2>;
+
+/// Read-write borrow of a three-dimensional array which is written back when dropped.
+pub type PyWritebackArray3<'py, T> = PyWritebackArray<'py, T, Ix3>;
+
+/// Read-write borrow of a four-dimensional array which is written back when dropped.
+pub type PyWritebackArray4<'py, T> = PyWritebackArray<'py, T, Ix4>;
+
+/// Read-write borrow of a five-dimensional array which is written back when dropped.
+pub type PyWritebackArray5<'py, T> = PyWritebackArray<'py, T, Ix5>;
+
+/// Read-write borrow of a six-dimensional array which is written back when dropped.
+pub type PyWritebackArray6<'py, T> = PyWritebackArray<'py, T, Ix6>;
+
+/// Read-write borrow of an array whose dimensionality is determined at runtime which is written back when dropped.
+pub type PyWritebackArrayDyn<'py, T> = PyWritebackArray<'py, T, IxDyn>;
+
+impl<'py, T, D> Deref for PyWritebackArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    type Target = PyReadwriteArray<'py, T, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.array
+    }
+}
+
+impl<'py, T, D> PyWritebackArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    /// Returns `true` if the elements were copied into a temporary array which will be written back.
+    pub fn is_copy(&self) -> bool {
+        self.original.is_some()
+    }
+
+    /// Provides a mutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array_mut(&mut self) -> ArrayViewMut<T, D> {
+        self.array.as_array_mut()
+    }
+
+    /// Provide a mutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice_mut(&mut self) -> Result<&mut [T], NotContiguousError> {
+        self.array.as_slice_mut()
+    }
+
+    /// Provide a mutable reference to an element of the NumPy array if the index is within bounds.
+    #[inline(always)]
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut T>
+    where
+        I: NpyIndex<Dim = D>,
+    {
+        self.array.get_mut(index)
+    }
+}
+
+impl<'py, T: Element, D: Dimension> FromPyObject<'py> for PyWritebackArray<'py, T, D> {
+    #[track_caller]
+    fn extract(ob: &'py PyAny) -> PyResult<Self> {
+        if let Ok(array) = ob.downcast::<PyArray<T, D>>() {
+            if array.is_contiguous() {
+                return Ok(Self {
+                    array: ManuallyDrop::new(array.try_readwrite()?),
+                    original: None,
+                });
+            }
+        }
+
+        let py = ob.py();
+
+        let original: &'py PyUntypedArray = ob.downcast()?;
+
+        let dims = D::from_dimension(&Dim(original.shape())).ok_or_else(|| {
+            DimensionalityError::new(original.ndim(), D::NDIM.unwrap_or_default())
+        })?;
+
+        let dtype = T::get_dtype(py);
+
+        for &(from, to) in &[(original.dtype(), dtype), (dtype, original.dtype())] {
+            let can_cast = unsafe {
+                PY_ARRAY_API.PyArray_CanCastTypeTo(
+                    py,
+                    from.as_dtype_ptr(),
+                    to.as_dtype_ptr(),
+                    Casting::SameKind.into(),
+                )
+            };
+
+            if can_cast == 0 {
+                return Err(CastError::new(from, to, Casting::SameKind).into());
+            }
+        }
+
+        if unsafe { (*original.as_array_ptr()).flags } & NPY_ARRAY_WRITEABLE == 0 {
+            return Err(BorrowError::NotWriteable.into());
+        }
+
+        acquire_mut(py, original.as_array_ptr(), Location::caller())?;
+
+        let array = PyArray::<T, D>::zeros(py, dims, false);
+
+        let res = unsafe {
+            if PY_ARRAY_API.PyArray_CopyInto(py, array.as_array_ptr(), original.as_array_ptr()) < 0
+            {
+                -1
+            } else {
+                // `PyArray_SetWritebackIfCopyBase` steals a reference to the base array.
+                ffi::Py_INCREF(original.as_ptr());
+
+                PY_ARRAY_API.PyArray_SetWritebackIfCopyBase(
+                    py,
+                    array.as_array_ptr(),
+                    original.as_array_ptr(),
+                )
+            }
+        };
+
+        if res < 0 {
+            release_mut(py, original.as_array_ptr());
+
+            return Err(PyErr::fetch(py));
+        }
+
+        Ok(Self {
+            array: ManuallyDrop::new(array.readwrite()),
+            original: Some(original),
+        })
+    }
+}
+
+impl<'py, T, D> Drop for PyWritebackArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn drop(&mut self) {
+        // SAFETY: The field is not used after this point.
+        let array = unsafe { ManuallyDrop::take(&mut self.array) };
+        let copy = array.as_array_ptr();
+
+        // The borrow of the copy is recorded relative to the base of the original array
+        // which `PyArray_ResolveWritebackIfCopy` detaches the copy from, hence it is released first.
+        drop(array);
+
+        if let Some(original) = self.original {
+            let py = original.py();
+
+            let res = unsafe { PY_ARRAY_API.PyArray_ResolveWritebackIfCopy(py, copy) };
+
+            if res < 0 {
+                PyErr::fetch(py).print(py);
+            }
+
+            release_mut(py, original.as_array_ptr());
+        }
+    }
+}
+
+impl<'py, T, D> fmt::Debug for PyWritebackArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "PyWritebackArray<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name)
+            .field("is_copy", &self.is_copy())
+            .finish()
+    }
+}
@@ -0,0 +1,180 @@
+//! Opt-in diagnostics describing the active borrows
+//!
+//! See [`set_diagnostics`] and [`active_borrows`].
+
+use std::fmt;
+use std::ops::Range;
+
+use pyo3::Python;
+
+use super::shared;
+
+/// Enables or disables recording where borrows are acquired.
+///
+/// While diagnostics are enabled, the location at which each borrow was acquired is recorded
+/// and failing to borrow an array yields [`BorrowError::AlreadyBorrowedBy`][crate::BorrowError::AlreadyBorrowedBy]
+/// describing one of the conflicting borrows instead of [`BorrowError::AlreadyBorrowed`][crate::BorrowError::AlreadyBorrowed].
+///
+/// Diagnostics apply to the borrows of all extensions using this crate and are disabled by default
+/// as recording the locations adds overhead to acquiring and releasing borrows.
+/// Disabling diagnostics discards all recorded locations.
+///
+/// # Example
+///
+/// ```
+/// use numpy::{borrow::set_diagnostics, BorrowError, PyArray1};
+/// use pyo3::Python;
+///
+/// Python::with_gil(|py| {
+///     set_diagnostics(py, true);
+///
+///     let array = PyArray1::<f64>::zeros(py, 10, false);
+///
+///     let (_shared, line) = (array.readonly(), line!());
+///
+///     match array.try_readwrite() {
+///         Err(BorrowError::AlreadyBorrowedBy(info)) => {
+///             assert_eq!(info.shape(), &[10]);
+///             assert_eq!(info.location().unwrap().line(), line);
+///         }
+///         _ => unreachable!(),
+///     }
+///
+///     set_diagnostics(py, false);
+/// });
+/// ```
+pub fn set_diagnostics(py: Python, enabled: bool) {
+    shared::set_diagnostics(py, enabled);
+}
+
+/// Lists the currently active borrows of all extensions using this crate.
+///
+/// Locations are only available for borrows acquired while [diagnostics are enabled][set_diagnostics].
+///
+/// # Example
+///
+/// ```
+/// use numpy::{borrow::{active_borrows, BorrowKind}, PyArray2};
+/// use pyo3::Python;
+///
+/// Python::with_gil(|py| {
+///     let array = PyArray2::<f64>::zeros(py, (2, 3), false);
+///
+///     let _exclusive = array.readwrite();
+///
+///     let borrows = active_borrows(py);
+///     let info = borrows.iter().find(|info| info.shape() == [2, 3]).unwrap();
+///
+///     assert_eq!(info.kind(), BorrowKind::Exclusive);
+///     assert_eq!(info.strides(), &[24, 8]);
+/// });
+/// ```
+pub fn active_borrows(py: Python) -> Vec<BorrowInfo> {
+    shared::active_borrows(py)
+}
+
+/// The kind of an active borrow, cf. [`BorrowInfo::kind`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorrowKind {
+    /// One or more borrows via [`PyReadonlyArray`][super::PyReadonlyArray]
+    Shared,
+    /// A single borrow via [`PyReadwriteArray`][super::PyReadwriteArray]
+    Exclusive,
+}
+
+/// Describes an active borrow, cf. [`active_borrows`].
+///
+/// Shared borrows of arrays with identical layout are tracked together
+/// and are therefore described by a single instance of this type.
+#[derive(Clone, Debug)]
+pub struct BorrowInfo {
+    pub(super) kind: BorrowKind,
+    pub(super) count: usize,
+    pub(super) shape: Vec<usize>,
+    pub(super) strides: Vec<isize>,
+    pub(super) data_range: Range<usize>,
+    pub(super) location: Option<BorrowLocation>,
+}
+
+impl BorrowInfo {
+    /// Returns whether the borrow is shared or exclusive.
+    pub fn kind(&self) -> BorrowKind {
+        self.kind
+    }
+
+    /// Returns the number of borrows described by this instance.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Returns the shape of the borrowed array.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Returns the strides of the borrowed array in bytes.
+    pub fn strides(&self) -> &[isize] {
+        &self.strides
+    }
+
+    /// Returns the addresses of the lowest and past the highest byte covered by the borrowed array.
+    pub fn data_range(&self) -> Range<usize> {
+        self.data_range.clone()
+    }
+
+    /// Returns where the first of the described borrows was acquired if diagnostics were enabled at the time.
+    pub fn location(&self) -> Option<&BorrowLocation> {
+        self.location.as_ref()
+    }
+}
+
+impl fmt::Display for BorrowInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            BorrowKind::Shared => write!(f, "{} shared borrow(s)", self.count)?,
+            BorrowKind::Exclusive => write!(f, "exclusive borrow")?,
+        }
+
+        write!(
+            f,
+            " of array with shape {:?} and strides {:?} covering {:#x}..{:#x}",
+            self.shape, self.strides, self.data_range.start, self.data_range.end
+        )?;
+
+        match &self.location {
+            Some(location) => write!(f, " acquired at {}", location),
+            None => write!(f, " acquired at unknown location"),
+        }
+    }
+}
+
+/// The source location at which a borrow was acquired, cf. [`BorrowInfo::location`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BorrowLocation {
+    pub(super) file: String,
+    pub(super) line: u32,
+    pub(super) column: u32,
+}
+
+impl BorrowLocation {
+    /// Returns the name of the source file.
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// Returns the line number.
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    /// Returns the column number.
+    pub fn column(&self) -> u32 {
+        self.column
+    }
+}
+
+impl fmt::Display for BorrowLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)
+    }
+}
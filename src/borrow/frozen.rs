@@ -0,0 +1,227 @@
+//! Arrays which are permanently frozen and can be accessed without dynamic borrow checking
+//!
+//! See [`PyArray::freeze`][crate::PyArray::freeze].
+
+use std::any::type_name;
+use std::fmt;
+use std::panic::Location;
+use std::slice::from_raw_parts;
+
+use ndarray::{ArrayView, Dimension, Ix0, Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn, RawArrayView};
+use pyo3::{FromPyObject, IntoPy, Py, PyAny, PyObject, PyResult, Python, ToPyObject};
+
+use super::shared::{freeze, is_frozen};
+use crate::array::PyArray;
+use crate::convert::NpyIndex;
+use crate::dtype::Element;
+use crate::error::{BorrowError, NotContiguousError};
+
+/// Permanently frozen array, cf. [`PyArray::freeze`].
+///
+/// Freezing an array acquires a shared borrow which is never released and clears its `WRITEABLE` flag.
+/// Hence the interior of the array can be accessed without consulting the global borrow flags,
+/// i.e. [`as_array`][Self::as_array] does not have any bookkeeping overhead which makes this type suitable
+/// for large lookup tables which are not modified by Rust code.
+///
+/// Like for [`PyReadonlyArray`][crate::PyReadonlyArray], only access from Rust is checked: Python code can still modify
+/// the data through the base object or other views of the array, or by setting the `WRITEABLE` flag again,
+/// and it is the responsibility of that code not to do so.
+///
+/// The frozen array and its entry in the global borrow flags are leaked for the remainder of the process,
+/// so only long-lived arrays should be frozen instead of temporaries created on each call.
+///
+/// This type can be extracted from arrays which were frozen before, e.g. as a function argument,
+/// and extraction fails with [`BorrowError::NotFrozen`] otherwise.
+/// It is [`Send`] and [`Sync`] if the element type is [`Sync`] as the array is kept alive indefinitely.
+///
+/// # Example
+///
+/// ```
+/// use numpy::{PyArray1, PyFrozenArray1};
+/// use pyo3::{Python, ToPyObject};
+///
+/// Python::with_gil(|py| {
+///     let table = PyArray1::from_vec(py, vec![1, 2, 4, 8]).freeze();
+///     assert_eq!(table.as_slice().unwrap(), &[1, 2, 4, 8]);
+///
+///     let obj = table.to_object(py);
+///     let table: PyFrozenArray1<i32> = obj.extract(py).unwrap();
+///     assert_eq!(table.get(2), Some(&4));
+///
+///     assert!(table.as_pyarray(py).try_readwrite().is_err());
+/// });
+/// ```
+pub struct PyFrozenArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    array: Py<PyArray<T, D>>,
+    view: RawArrayView<T, D>,
+}
+
+/// Frozen zero-dimensional array.
+pub type PyFrozenArray0<T> = PyFrozenArray<T, Ix0>;
+
+/// Frozen one-dimensional array.
+pub type PyFrozenArray1<T> = PyFrozenArray<T, Ix1>;
+
+/// Frozen two-dimensional array.
+pub type PyFrozenArray2<T> = PyFrozenArray<T, Ix2>;
+
+/// Frozen three-dimensional array.
+pub type PyFrozenArray3<T> = PyFrozenArray<T, Ix3>;
+
+/// Frozen four-dimensional array.
+pub type PyFrozenArray4<T> = PyFrozenArray<T, Ix4>;
+
+/// Frozen five-dimensional array.
+pub type PyFrozenArray5<T> = PyFrozenArray<T, Ix5>;
+
+/// Frozen six-dimensional array.
+pub type PyFrozenArray6<T> = PyFrozenArray<T, Ix6>;
+
+/// Frozen array whose dimensionality is determined at runtime.
+pub type PyFrozenArrayDyn<T> = PyFrozenArray<T, IxDyn>;
+
+// SAFETY: The array is never modified by safe Rust code and kept alive indefinitely.
+unsafe impl<T, D> Send for PyFrozenArray<T, D>
+where
+    T: Element + Sync,
+    D: Dimension,
+{
+}
+
+unsafe impl<T, D> Sync for PyFrozenArray<T, D>
+where
+    T: Element + Sync,
+    D: Dimension,
+{
+}
+
+impl<'py, T: Element + 'py, D: Dimension + 'py> FromPyObject<'py> for PyFrozenArray<T, D> {
+    fn extract(obj: &'py PyAny) -> PyResult<Self> {
+        let array: &'py PyArray<T, D> = obj.extract()?;
+
+        if !is_frozen(array.py(), array.as_array_ptr()) {
+            return Err(BorrowError::NotFrozen.into());
+        }
+
+        Ok(Self::new(array))
+    }
+}
+
+impl<T, D> PyFrozenArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    #[track_caller]
+    pub(crate) fn try_new(array: &PyArray<T, D>) -> Result<Self, BorrowError> {
+        freeze(array.py(), array.as_array_ptr(), Location::caller())?;
+
+        Ok(Self::new(array))
+    }
+
+    fn new(array: &PyArray<T, D>) -> Self {
+        // SAFETY: The array is frozen and hence not modified by safe Rust code.
+        let view = unsafe { array.as_array() }.raw_view();
+
+        Self {
+            array: array.into(),
+            view,
+        }
+    }
+
+    /// Provides an immutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array(&self) -> ArrayView<T, D> {
+        // SAFETY: The array is frozen and kept alive indefinitely.
+        unsafe { self.view.clone().deref_into_view() }
+    }
+
+    /// Provide an immutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice(&self) -> Result<&[T], NotContiguousError> {
+        let view = self.as_array();
+        let slice = view.as_slice_memory_order().ok_or(NotContiguousError)?;
+
+        // SAFETY: The array is frozen and kept alive indefinitely.
+        Ok(unsafe { from_raw_parts(slice.as_ptr(), slice.len()) })
+    }
+
+    /// Provide an immutable reference to an element of the NumPy array if the index is within bounds.
+    #[inline(always)]
+    pub fn get<I>(&self, index: I) -> Option<&T>
+    where
+        I: NpyIndex<Dim = D>,
+    {
+        let index = index.into_dimension();
+        let index = index.slice();
+        let shape = self.view.shape();
+
+        if index.len() != shape.len() || index.iter().zip(shape).any(|(i, d)| i >= d) {
+            return None;
+        }
+
+        // The strides of the view are given in units of elements.
+        let offset = index
+            .iter()
+            .zip(self.view.strides())
+            .map(|(&i, stride)| i as isize * stride)
+            .sum();
+
+        // SAFETY: The index was checked and the array is frozen and kept alive indefinitely.
+        Some(unsafe { &*self.view.as_ptr().offset(offset) })
+    }
+
+    /// Provides access to the frozen NumPy array.
+    pub fn as_pyarray<'py>(&'py self, py: Python<'py>) -> &'py PyArray<T, D> {
+        self.array.as_ref(py)
+    }
+}
+
+impl<T, D> Clone for PyFrozenArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn clone(&self) -> Self {
+        Self {
+            array: self.array.clone(),
+            view: self.view.clone(),
+        }
+    }
+}
+
+impl<T, D> ToPyObject for PyFrozenArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn to_object(&self, py: Python) -> PyObject {
+        self.array.to_object(py)
+    }
+}
+
+impl<T, D> IntoPy<PyObject> for PyFrozenArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn into_py(self, py: Python) -> PyObject {
+        self.array.into_py(py)
+    }
+}
+
+impl<T, D> fmt::Debug for PyFrozenArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!("PyFrozenArray<{}, {}>", type_name::<T>(), type_name::<D>());
+
+        f.debug_struct(&name).finish()
+    }
+}
@@ -0,0 +1,1207 @@
+//! Types to safely create references into NumPy arrays
+//!
+//! It is assumed that unchecked code - which includes unsafe Rust and Python - is validated by its author
+//! which together with the dynamic borrow checking performed by this crate ensures that
+//! safe Rust code cannot cause undefined behaviour by creating references into NumPy arrays.
+//!
+//! With these borrows established, [references to individual elements][PyReadonlyArray::get] or [reference-based views of whole array][PyReadonlyArray::as_array]
+//! can be created safely. These are then the starting point for algorithms iteraing over and operating on the elements of the array.
+//!
+//! # Examples
+//!
+//! The first example shows that dynamic borrow checking works to constrain
+//! both what safe Rust code can invoke and how it is invoked.
+//!
+//! ```rust
+//! # use std::panic::{catch_unwind, AssertUnwindSafe};
+//! #
+//! use numpy::PyArray1;
+//! use ndarray::Zip;
+//! use pyo3::Python;
+//!
+//! fn add(x: &PyArray1<f64>, y: &PyArray1<f64>, z: &PyArray1<f64>) {
+//!     let x1 = x.readonly();
+//!     let y1 = y.readonly();
+//!     let mut z1 = z.readwrite();
+//!
+//!     let x2 = x1.as_array();
+//!     let y2 = y1.as_array();
+//!     let z2 = z1.as_array_mut();
+//!
+//!     Zip::from(x2)
+//!         .and(y2)
+//!         .and(z2)
+//!         .for_each(|x3, y3, z3| *z3 = x3 + y3);
+//!
+//!     // Will fail at runtime due to conflict with `x1`.
+//!     let res = catch_unwind(AssertUnwindSafe(|| {
+//!         let _x4 = x.readwrite();
+//!     }));
+//!     assert!(res.is_err());
+//! }
+//!
+//! Python::with_gil(|py| {
+//!     let x = PyArray1::<f64>::zeros(py, 42, false);
+//!     let y = PyArray1::<f64>::zeros(py, 42, false);
+//!     let z = PyArray1::<f64>::zeros(py, 42, false);
+//!
+//!     // Will work as the three arrays are distinct.
+//!     add(x, y, z);
+//!
+//!     // Will work as `x1` and `y1` are compatible borrows.
+//!     add(x, x, z);
+//!
+//!     // Will fail at runtime due to conflict between `y1` and `z1`.
+//!     let res = catch_unwind(AssertUnwindSafe(|| {
+//!         add(x, y, y);
+//!     }));
+//!     assert!(res.is_err());
+//! });
+//! ```
+//!
+//! The second example shows that non-overlapping and interleaved views are also supported.
+//!
+//! ```rust
+//! use numpy::PyArray1;
+//! use pyo3::{types::IntoPyDict, Python};
+//!
+//! Python::with_gil(|py| {
+//!     let array = PyArray1::arange(py, 0.0, 10.0, 1.0);
+//!     let locals = [("array", array)].into_py_dict(py);
+//!
+//!     let view1 = py.eval("array[:5]", None, Some(locals)).unwrap().downcast::<PyArray1<f64>>().unwrap();
+//!     let view2 = py.eval("array[5:]", None, Some(locals)).unwrap().downcast::<PyArray1<f64>>().unwrap();
+//!     let view3 = py.eval("array[::2]", None, Some(locals)).unwrap().downcast::<PyArray1<f64>>().unwrap();
+//!     let view4 = py.eval("array[1::2]", None, Some(locals)).unwrap().downcast::<PyArray1<f64>>().unwrap();
+//!
+//!     {
+//!         let _view1 = view1.readwrite();
+//!         let _view2 = view2.readwrite();
+//!     }
+//!
+//!     {
+//!         let _view3 = view3.readwrite();
+//!         let _view4 = view4.readwrite();
+//!     }
+//! });
+//! ```
+//!
+//! The third example shows that interleaved views are supported even if the step size does not divide the dimension.
+//!
+//! ```rust
+//! # use std::panic::{catch_unwind, AssertUnwindSafe};
+//! #
+//! use numpy::PyArray2;
+//! use pyo3::{types::IntoPyDict, Python};
+//!
+//! Python::with_gil(|py| {
+//!     let array = PyArray2::<f64>::zeros(py, (10, 10), false);
+//!     let locals = [("array", array)].into_py_dict(py);
+//!
+//!     let view1 = py.eval("array[:, ::3]", None, Some(locals)).unwrap().downcast::<PyArray2<f64>>().unwrap();
+//!     let view2 = py.eval("array[:, 1::3]", None, Some(locals)).unwrap().downcast::<PyArray2<f64>>().unwrap();
+//!     let view3 = py.eval("array[:, 1::2]", None, Some(locals)).unwrap().downcast::<PyArray2<f64>>().unwrap();
+//!
+//!     // Will work as the views do not share any elements.
+//!     {
+//!         let _view1 = view1.readwrite();
+//!         let _view2 = view2.readwrite();
+//!     }
+//!
+//!     // Will fail at runtime as e.g. `array[0, 1]` is part of both views.
+//!     let res = catch_unwind(AssertUnwindSafe(|| {
+//!         let _view2 = view2.readwrite();
+//!         let _view3 = view3.readwrite();
+//!     }));
+//!     assert!(res.is_err());
+//! });
+//! ```
+//!
+//! # Diagnostics
+//!
+//! Calling [`set_diagnostics`] records the source location at which each borrow is acquired.
+//! Failing to borrow an array then yields [`BorrowError::AlreadyBorrowedBy`] describing one of the conflicting borrows
+//! including its kind, shape, strides, data range and location. All active borrows can be listed using [`active_borrows`].
+//!
+//! # Strict mode
+//!
+//! Calling [`set_strict`] additionally clears the [`WRITEABLE`][writeable] flag of arrays while they are borrowed shared
+//! so that Python code cannot modify them through the borrowed array object, and restores it after the last shared borrow is released.
+//! Arrays which are not writeable or which are views into arrays which are not writeable are then refused by [`PyReadwriteArray`],
+//! so that Rust code cannot modify an array which Python has locked.
+//!
+//! [writeable]: https://numpy.org/doc/stable/reference/c-api/array.html#c.NPY_ARRAY_WRITEABLE
+//!
+//! # Frozen arrays
+//!
+//! Long-lived arrays which are not modified by Rust code, e.g. large lookup tables, can be [frozen][PyArray::freeze]
+//! which permanently acquires a shared borrow. The resulting [`PyFrozenArray`] provides access to the array without
+//! any further bookkeeping. Frozen arrays are leaked for the remainder of the process and, as for other borrows,
+//! Python code can still modify them through their base object or other views.
+//!
+//! # Rationale
+//!
+//! Rust references require aliasing discipline to be maintained, i.e. there must always
+//! exist only a single mutable (aka exclusive) reference or multiple immutable (aka shared) references
+//! for each object, otherwise the program contains undefined behaviour.
+//!
+//! The aim of this module is to ensure that safe Rust code is unable to violate these requirements on its own.
+//! We cannot prevent unchecked code - this includes unsafe Rust, Python or other native code like C or Fortran -
+//! from violating them. Therefore the responsibility to avoid this lies with the author of that code instead of the compiler.
+//! However, assuming that the unchecked code is correct, we can ensure that safe Rust is unable to introduce mistakes
+//! into an otherwise correct program by dynamically checking which arrays are currently borrowed and in what manner.
+//!
+//! This means that we follow the [base object chain][base] of each array to the original allocation backing it and
+//! track which parts of that allocation are covered by the array and thereby ensure that only a single read-write array
+//! or multiple read-only arrays overlapping with that region are borrowed at any time.
+//!
+//! The state of these borrows is shared by all extensions built using this crate, even if they were compiled against
+//! different versions of it, via a versioned capsule stored in the `_RUST_NUMPY_BORROW_CHECKING_API` attribute of
+//! NumPy's `numpy.core.multiarray` module. Hence an array borrowed by one extension is also considered borrowed
+//! when passed into another one, e.g. when one extension calls back into the other.
+//!
+//! In contrast to Rust references, the mere existence of Python references or raw pointers is not an issue
+//! because these values are not assumed to follow aliasing discipline by the Rust compiler.
+//!
+//! This cannot prevent unchecked code from concurrently modifying an array via callbacks or using multiple threads,
+//! but that would lead to incorrect results even if the code that is interfered with is implemented in another language
+//! which does not require aliasing discipline.
+//!
+//! Concerning multi-threading in particular: While the GIL needs to be acquired to create borrows, they are not bound to the GIL
+//! and will stay active after the GIL is released, for example by calling [`allow_threads`][pyo3::Python::allow_threads].
+//! [`PyReadonlyArray::detach_view`] and [`PyReadwriteArray::detach_view`] turn borrows into guards which can be used
+//! while the GIL is released or sent to other threads and which will acquire the GIL to release the borrow when dropped.
+//! [`PyReadonlyArray::into_shared`] yields a reference-counted handle which is not bound to any lifetime at all.
+//! Borrows also do not provide synchronization, i.e. multiple threads borrowing the same array will lead to runtime panics,
+//! it will not block those threads until already active borrows are released.
+//!
+//! In summary, this crate takes the position that all unchecked code - unsafe Rust, Python, C, Fortran, etc. - must be checked for correctness by its author.
+//! Safe Rust code can then rely on this correctness, but should not be able to introduce memory safety issues on its own. Additionally, dynamic borrow checking
+//! can catch _some_ mistakes introduced by unchecked code, e.g. Python calling a function with the same array as an input and as an output argument.
+//!
+//! # Limitations
+//!
+//! Borrows of arrays which have the same object at the end of their [base object chain][base] are checked for conflicts
+//! by determining whether the arrays actually share any elements. This requires solving a bounded linear Diophantine equation
+//! combining the data pointers, dimensions and strides of both arrays, which can take time exponential in the number of dimensions.
+//!
+//! Hence the search for a solution is aborted after a fixed number of steps and the borrows are considered conflicting,
+//! i.e. the check is still an over-approximation for complicated memory layouts. This is similar to rustc itself
+//! which ensures that all accepted programs are memory safe but does not necessarily accept all memory safe programs.
+//! However, the unsafe method [`PyArray::as_array_mut`] can be used as an escape hatch.
+//!
+//! [base]: https://numpy.org/doc/stable/reference/c-api/types-and-structures.html#c.NPY_AO.base
+
+mod diagnostics;
+mod frozen;
+mod shared;
+mod strict;
+
+use std::any::type_name;
+use std::fmt;
+use std::mem::forget;
+use std::ops::Deref;
+use std::panic::Location;
+use std::slice::{from_raw_parts, from_raw_parts_mut};
+use std::sync::Arc;
+
+#[cfg(feature = "slice-view")]
+use ndarray::SliceArg;
+use ndarray::{
+    ArrayView, ArrayViewMut, Axis, Dimension, IntoDimension, Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn,
+    RawArrayView, RawArrayViewMut, RemoveAxis, ShapeError, Slice,
+};
+use pyo3::{AsPyPointer, FromPyObject, Py, PyAny, PyResult, Python};
+
+use crate::array::{PyArray, SortKind};
+use crate::convert::NpyIndex;
+use crate::dtype::Element;
+use crate::error::{BorrowError, NotContiguousError};
+use crate::npyffi::{PyArrayObject, NPY_ARRAY_WRITEABLE};
+
+pub(crate) use self::shared::{acquire, acquire_mut, release, release_mut};
+
+pub use self::diagnostics::{
+    active_borrows, set_diagnostics, BorrowInfo, BorrowKind, BorrowLocation,
+};
+pub use self::frozen::{
+    PyFrozenArray, PyFrozenArray0, PyFrozenArray1, PyFrozenArray2, PyFrozenArray3, PyFrozenArray4,
+    PyFrozenArray5, PyFrozenArray6, PyFrozenArrayDyn,
+};
+pub use self::strict::set_strict;
+
+/// Read-only borrow of an array.
+///
+/// An instance of this type ensures that there are no instances of [`PyReadwriteArray`],
+/// i.e. that only shared references into the interior of the array can be created safely.
+///
+/// See the [module-level documentation](self) for more.
+#[repr(C)]
+pub struct PyReadonlyArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    array: &'py PyArray<T, D>,
+}
+
+/// Read-only borrow of a one-dimensional array.
+pub type PyReadonlyArray1<'py, T> = PyReadonlyArray<'py, T, Ix1>;
+
+/// Read-only borrow of a two-dimensional array.
+pub type PyReadonlyArray2<'py, T> = PyReadonlyArray<'py, T, Ix2>;
+
+/// Read-only borrow of a three-dimensional array.
+pub type PyReadonlyArray3<'py, T> = PyReadonlyArray<'py, T, Ix3>;
+
+/// Read-only borrow of a four-dimensional array.
+pub type PyReadonlyArray4<'py, T> = PyReadonlyArray<'py, T, Ix4>;
+
+/// Read-only borrow of a five-dimensional array.
+pub type PyReadonlyArray5<'py, T> = PyReadonlyArray<'py, T, Ix5>;
+
+/// Read-only borrow of a six-dimensional array.
+pub type PyReadonlyArray6<'py, T> = PyReadonlyArray<'py, T, Ix6>;
+
+/// Read-only borrow of an array whose dimensionality is determined at runtime.
+pub type PyReadonlyArrayDyn<'py, T> = PyReadonlyArray<'py, T, IxDyn>;
+
+impl<'py, T, D> Deref for PyReadonlyArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    type Target = PyArray<T, D>;
+
+    fn deref(&self) -> &Self::Target {
+        self.array
+    }
+}
+
+impl<'py, T: Element, D: Dimension> FromPyObject<'py> for PyReadonlyArray<'py, T, D> {
+    #[track_caller]
+    fn extract(obj: &'py PyAny) -> PyResult<Self> {
+        let array: &'py PyArray<T, D> = obj.extract()?;
+        Ok(array.readonly())
+    }
+}
+
+impl<'py, T, D> PyReadonlyArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    #[track_caller]
+    pub(crate) fn try_new(array: &'py PyArray<T, D>) -> Result<Self, BorrowError> {
+        acquire(array.py(), array.as_array_ptr(), Location::caller())?;
+
+        Ok(Self { array })
+    }
+
+    /// Provides an immutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array(&self) -> ArrayView<T, D> {
+        // SAFETY: Global borrow flags ensure aliasing discipline.
+        unsafe { self.array.as_array() }
+    }
+
+    /// Provide an immutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice(&self) -> Result<&[T], NotContiguousError> {
+        // SAFETY: Global borrow flags ensure aliasing discipline.
+        unsafe { self.array.as_slice() }
+    }
+
+    /// Provides an immutable array view of the interior of the NumPy array
+    /// which views its trailing axis of length `N` as elements of type `[T; N]`.
+    ///
+    /// See [`PyArray::as_array_of`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 0.0_f32, 12.0, 1.0).reshape([4, 3]).unwrap();
+    ///     let pyarray = pyarray.readonly();
+    ///
+    ///     let points = pyarray.as_array_of::<3>().unwrap();
+    ///     assert_eq!(points.shape(), [4]);
+    ///     assert_eq!(points[1], [3.0, 4.0, 5.0]);
+    ///
+    ///     assert!(pyarray.as_array_of::<4>().is_err());
+    /// });
+    /// ```
+    #[inline(always)]
+    pub fn as_array_of<const N: usize>(&self) -> Result<ArrayView<[T; N], D::Smaller>, ShapeError>
+    where
+        D: RemoveAxis,
+    {
+        // SAFETY: Global borrow flags ensure aliasing discipline.
+        unsafe { self.array.as_array_of() }
+    }
+
+    /// Provide an immutable reference to an element of the NumPy array if the index is within bounds.
+    #[inline(always)]
+    pub fn get<I>(&self, index: I) -> Option<&T>
+    where
+        I: NpyIndex<Dim = D>,
+    {
+        unsafe { self.array.get(index) }
+    }
+
+    /// Creates a NumPy view of the array sliced according to `info` and borrows it immutably.
+    ///
+    /// See [`PyArray::slice_view`] for details.
+    #[cfg(feature = "slice-view")]
+    #[track_caller]
+    pub fn slice_view<I>(&self, info: I) -> PyReadonlyArray<'py, T, I::OutDim>
+    where
+        I: SliceArg<D>,
+    {
+        PyReadonlyArray::try_new(self.array.slice_view(info)).unwrap()
+    }
+
+    /// Detaches the borrow from the GIL so that the array can be accessed while the GIL is released,
+    /// e.g. within [`allow_threads`][pyo3::Python::allow_threads].
+    ///
+    /// The borrow stays active until the returned guard is dropped which will acquire the GIL to release it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 0.0, 100.0, 1.0);
+    ///
+    ///     let view = pyarray.readonly().detach_view();
+    ///
+    ///     let sum = py.allow_threads(|| view.as_array().sum());
+    ///     assert_eq!(sum, 4950.0);
+    ///
+    ///     assert!(pyarray.try_readwrite().is_err());
+    ///     drop(view);
+    ///     assert!(pyarray.try_readwrite().is_ok());
+    /// });
+    /// ```
+    pub fn detach_view(self) -> DetachedReadonlyArray<T, D> {
+        let array = self.array.to_owned();
+
+        let view = self.as_array().raw_view();
+
+        // The guard takes over the responsibility to release the borrow.
+        forget(self);
+
+        DetachedReadonlyArray { array, view }
+    }
+
+    /// Turns the borrow into a handle which can be cloned and shared with other threads without restrictions,
+    /// e.g. to pass the array to a thread pool or background task without copying it.
+    ///
+    /// The borrow stays active until the last clone of the returned handle is dropped
+    /// which will acquire the GIL to release it, cf. [`detach_view`][Self::detach_view].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::thread::spawn;
+    ///
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// let array = Python::with_gil(|py| PyArray::arange(py, 0, 100, 1).readonly().into_shared());
+    ///
+    /// let handles = (0..4)
+    ///     .map(|idx| {
+    ///         let array = array.clone();
+    ///         spawn(move || array.as_array().iter().skip(idx).step_by(4).sum::<i64>())
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// let sum = handles.into_iter().map(|handle| handle.join().unwrap()).sum::<i64>();
+    /// assert_eq!(sum, 4950);
+    /// ```
+    pub fn into_shared(self) -> SharedReadonlyArray<T, D> {
+        SharedReadonlyArray(Arc::new(self.detach_view()))
+    }
+}
+
+impl<'a, T, D> Clone for PyReadonlyArray<'a, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    #[track_caller]
+    fn clone(&self) -> Self {
+        acquire(
+            self.array.py(),
+            self.array.as_array_ptr(),
+            Location::caller(),
+        )
+        .unwrap();
+
+        Self { array: self.array }
+    }
+}
+
+impl<'a, T, D> Drop for PyReadonlyArray<'a, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn drop(&mut self) {
+        release(self.array.py(), self.array.as_array_ptr());
+    }
+}
+
+impl<'py, T, D> fmt::Debug for PyReadonlyArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "PyReadonlyArray<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name).finish()
+    }
+}
+
+/// Read-write borrow of an array.
+///
+/// An instance of this type ensures that there are no instances of [`PyReadonlyArray`] and no other instances of [`PyReadwriteArray`],
+/// i.e. that only a single exclusive reference into the interior of the array can be created safely.
+///
+/// See the [module-level documentation](self) for more.
+#[repr(C)]
+pub struct PyReadwriteArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    array: &'py PyArray<T, D>,
+}
+
+/// Read-write borrow of a one-dimensional array.
+pub type PyReadwriteArray1<'py, T> = PyReadwriteArray<'py, T, Ix1>;
+
+/// Read-write borrow of a two-dimensional array.
+pub type PyReadwriteArray2<'py, T> = PyReadwriteArray<'py, T, Ix2>;
+
+/// Read-write borrow of a three-dimensional array.
+pub type PyReadwriteArray3<'py, T> = PyReadwriteArray<'py, T, Ix3>;
+
+/// Read-write borrow of a four-dimensional array.
+pub type PyReadwriteArray4<'py, T> = PyReadwriteArray<'py, T, Ix4>;
+
+/// Read-write borrow of a five-dimensional array.
+pub type PyReadwriteArray5<'py, T> = PyReadwriteArray<'py, T, Ix5>;
+
+/// Read-write borrow of a six-dimensional array.
+pub type PyReadwriteArray6<'py, T> = PyReadwriteArray<'py, T, Ix6>;
+
+/// Read-write borrow of an array whose dimensionality is determined at runtime.
+pub type PyReadwriteArrayDyn<'py, T> = PyReadwriteArray<'py, T, IxDyn>;
+
+impl<'py, T, D> Deref for PyReadwriteArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    type Target = PyReadonlyArray<'py, T, D>;
+
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: Exclusive references decay implictly into shared references.
+        unsafe { &*(self as *const Self as *const Self::Target) }
+    }
+}
+
+impl<'py, T: Element, D: Dimension> FromPyObject<'py> for PyReadwriteArray<'py, T, D> {
+    #[track_caller]
+    fn extract(obj: &'py PyAny) -> PyResult<Self> {
+        let array: &'py PyArray<T, D> = obj.extract()?;
+        Ok(array.readwrite())
+    }
+}
+
+impl<'py, T, D> PyReadwriteArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    #[track_caller]
+    pub(crate) fn try_new(array: &'py PyArray<T, D>) -> Result<Self, BorrowError> {
+        acquire_mut(array.py(), array.as_array_ptr(), Location::caller())?;
+
+        // Checked after acquiring the borrow so that arrays locked by shared borrows
+        // in strict mode are reported as already borrowed instead of not writeable.
+        if !array.check_flags(NPY_ARRAY_WRITEABLE) {
+            release_mut(array.py(), array.as_array_ptr());
+
+            return Err(BorrowError::NotWriteable);
+        }
+
+        Ok(Self { array })
+    }
+
+    /// Provides a mutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array_mut(&mut self) -> ArrayViewMut<T, D> {
+        // SAFETY: Global borrow flags ensure aliasing discipline.
+        unsafe { self.array.as_array_mut() }
+    }
+
+    /// Provide a mutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice_mut(&mut self) -> Result<&mut [T], NotContiguousError> {
+        // SAFETY: Global borrow flags ensure aliasing discipline.
+        unsafe { self.array.as_slice_mut() }
+    }
+
+    /// Provides a mutable array view of the interior of the NumPy array
+    /// which views its trailing axis of length `N` as elements of type `[T; N]`.
+    ///
+    /// See [`PyArray::as_array_of`] for details.
+    #[inline(always)]
+    pub fn as_array_mut_of<const N: usize>(
+        &mut self,
+    ) -> Result<ArrayViewMut<[T; N], D::Smaller>, ShapeError>
+    where
+        D: RemoveAxis,
+    {
+        // SAFETY: Global borrow flags ensure aliasing discipline.
+        unsafe { self.array.as_array_mut_of() }
+    }
+
+    /// Provide a mutable reference to an element of the NumPy array if the index is within bounds.
+    #[inline(always)]
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut T>
+    where
+        I: NpyIndex<Dim = D>,
+    {
+        unsafe { self.array.get_mut(index) }
+    }
+
+    /// Sorts the elements of the array along the given `axis` in place.
+    ///
+    /// Safe wrapper for [`PyArray::sort`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::Axis;
+    /// use numpy::{PyArray, SortKind};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_slice(py, &[3, 1, 2]);
+    ///
+    ///     pyarray.readwrite().sort(Axis(0), SortKind::QuickSort).unwrap();
+    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[1, 2, 3]);
+    /// });
+    /// ```
+    pub fn sort(&mut self, axis: Axis, kind: SortKind) -> PyResult<()> {
+        // SAFETY: Exclusive borrow of `self` proves exclusive access to the interior of the array.
+        unsafe { self.array.sort(axis, kind) }
+    }
+
+    /// Partially sorts the elements of the array along the given `axis` in place.
+    ///
+    /// Safe wrapper for [`PyArray::partition`].
+    pub fn partition(&mut self, kth: &[usize], axis: Axis) -> PyResult<()> {
+        // SAFETY: Exclusive borrow of `self` proves exclusive access to the interior of the array.
+        unsafe { self.array.partition(kth, axis) }
+    }
+
+    /// Creates a NumPy view of the array sliced according to `info` and transfers the exclusive borrow to it.
+    ///
+    /// See [`PyArray::slice_view`] for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "slice-view")] {
+    /// use ndarray::s;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 0, 6, 1);
+    ///
+    ///     let mut view = pyarray.readwrite().slice_view(s![1..;2]);
+    ///     view.as_array_mut().fill(0);
+    ///     drop(view);
+    ///
+    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[0, 0, 2, 0, 4, 0]);
+    /// });
+    /// # }
+    /// ```
+    #[cfg(feature = "slice-view")]
+    #[track_caller]
+    pub fn slice_view<I>(self, info: I) -> PyReadwriteArray<'py, T, I::OutDim>
+    where
+        I: SliceArg<D>,
+    {
+        let view = self.array.slice_view(info);
+
+        drop(self);
+
+        PyReadwriteArray::try_new(view).unwrap()
+    }
+
+    /// Splits the borrow into two disjoint borrows of views of the array before and after `index` along `axis`.
+    ///
+    /// In contrast to [`ArrayViewMut::split_at`], the pieces are NumPy arrays which are tracked separately,
+    /// i.e. each of them can be released independently or returned to Python.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` or `index` is out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::{array, Axis};
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::from_owned_array(py, array![[1, 2, 3], [4, 5, 6]]);
+    ///
+    ///     let (mut left, right) = pyarray.readwrite().split_at(Axis(1), 1);
+    ///
+    ///     left.as_array_mut().fill(0);
+    ///     drop(left);
+    ///
+    ///     assert!(pyarray.try_readonly().is_err());
+    ///     assert_eq!(right.as_array(), array![[2, 3], [5, 6]]);
+    ///     drop(right);
+    ///
+    ///     assert_eq!(pyarray.readonly().as_array(), array![[0, 2, 3], [0, 5, 6]]);
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn split_at(self, axis: Axis, index: usize) -> (Self, Self) {
+        let array = self.array;
+
+        assert!(index <= array.shape()[axis.index()]);
+
+        let first = array.slice_axis_view(axis, Slice::from(..index));
+        let second = array.slice_axis_view(axis, Slice::from(index..));
+
+        drop(self);
+
+        (
+            PyReadwriteArray::try_new(first).unwrap(),
+            PyReadwriteArray::try_new(second).unwrap(),
+        )
+    }
+
+    /// Splits the borrow into disjoint borrows of views of the array which have length `size` along `axis`,
+    /// except for the last one which may be shorter.
+    ///
+    /// Similar to [`split_at`][Self::split_at], each chunk is a NumPy array which is tracked separately.
+    /// The part of the array not yet yielded stays borrowed until the iterator is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `axis` is out of bounds or `size` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use ndarray::Axis;
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 0, 10, 1);
+    ///
+    ///     let chunks = pyarray.readwrite().axis_chunks(Axis(0), 4).collect::<Vec<_>>();
+    ///     assert_eq!(chunks.len(), 3);
+    ///
+    ///     for mut chunk in chunks {
+    ///         let first = chunk.as_array()[0];
+    ///         chunk.as_array_mut().fill(first);
+    ///     }
+    ///
+    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[0, 0, 0, 0, 4, 4, 4, 4, 8, 8]);
+    /// });
+    /// ```
+    pub fn axis_chunks(self, axis: Axis, size: usize) -> PyReadwriteArrayChunks<'py, T, D> {
+        assert_ne!(size, 0, "Chunk size must be nonzero.");
+
+        let rest = if self.array.shape()[axis.index()] != 0 {
+            Some(self)
+        } else {
+            None
+        };
+
+        PyReadwriteArrayChunks { rest, axis, size }
+    }
+
+    /// Detaches the borrow from the GIL so that the array can be accessed while the GIL is released,
+    /// e.g. within [`allow_threads`][pyo3::Python::allow_threads].
+    ///
+    /// The borrow stays active until the returned guard is dropped which will acquire the GIL to release it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 0.0, 4.0, 1.0);
+    ///
+    ///     let mut view = pyarray.readwrite().detach_view();
+    ///
+    ///     py.allow_threads(move || view.as_array_mut().mapv_inplace(f64::sqrt));
+    ///
+    ///     assert_eq!(pyarray.readonly().as_slice().unwrap(), &[0.0, 1.0, 2.0_f64.sqrt(), 3.0_f64.sqrt()]);
+    /// });
+    /// ```
+    pub fn detach_view(mut self) -> DetachedReadwriteArray<T, D> {
+        let array = self.array.to_owned();
+
+        let view = self.as_array_mut().raw_view_mut();
+
+        // The guard takes over the responsibility to release the borrow.
+        forget(self);
+
+        DetachedReadwriteArray { array, view }
+    }
+}
+
+impl<'py, T> PyReadwriteArray<'py, T, Ix1>
+where
+    T: Element,
+{
+    /// Extends or truncates the dimensions of an array.
+    ///
+    /// Safe wrapper for [`PyArray::resize`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::PyArray;
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let pyarray = PyArray::arange(py, 0, 10, 1);
+    ///     assert_eq!(pyarray.len(), 10);
+    ///
+    ///     let pyarray = pyarray.readwrite();
+    ///     let pyarray = pyarray.resize(100).unwrap();
+    ///     assert_eq!(pyarray.len(), 100);
+    /// });
+    /// ```
+    #[track_caller]
+    pub fn resize<ID: IntoDimension>(self, dims: ID) -> PyResult<Self> {
+        let array = self.array;
+
+        // The borrow is released before resizing as its extent is determined by the current shape of the array.
+        drop(self);
+
+        // SAFETY: Ownership of `self` proved exclusive access to the interior of the array
+        // and the GIL prevents any other borrow from being acquired in the meantime.
+        unsafe {
+            array.resize(dims)?;
+        }
+
+        Ok(Self::try_new(array).unwrap())
+    }
+}
+
+impl<'a, T, D> Drop for PyReadwriteArray<'a, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn drop(&mut self) {
+        release_mut(self.array.py(), self.array.as_array_ptr());
+    }
+}
+
+impl<'py, T, D> fmt::Debug for PyReadwriteArray<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "PyReadwriteArray<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name).finish()
+    }
+}
+
+/// Iterator over disjoint borrows of chunks of an array, cf. [`PyReadwriteArray::axis_chunks`].
+pub struct PyReadwriteArrayChunks<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    rest: Option<PyReadwriteArray<'py, T, D>>,
+    axis: Axis,
+    size: usize,
+}
+
+impl<'py, T, D> Iterator for PyReadwriteArrayChunks<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    type Item = PyReadwriteArray<'py, T, D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest.take()?;
+
+        if rest.shape()[self.axis.index()] <= self.size {
+            return Some(rest);
+        }
+
+        let (chunk, rest) = rest.split_at(self.axis, self.size);
+        self.rest = Some(rest);
+
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'py, T, D> ExactSizeIterator for PyReadwriteArrayChunks<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn len(&self) -> usize {
+        match &self.rest {
+            Some(rest) => (rest.shape()[self.axis.index()] + self.size - 1) / self.size,
+            None => 0,
+        }
+    }
+}
+
+impl<'py, T, D> fmt::Debug for PyReadwriteArrayChunks<'py, T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "PyReadwriteArrayChunks<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name)
+            .field("axis", &self.axis)
+            .field("size", &self.size)
+            .finish()
+    }
+}
+
+/// Read-only borrow of an array which is not bound to the GIL, cf. [`PyReadonlyArray::detach_view`].
+///
+/// This type is [`Send`] and [`Sync`] if the element type is [`Sync`].
+/// Dropping it will acquire the GIL to release the borrow.
+pub struct DetachedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    array: Py<PyArray<T, D>>,
+    view: RawArrayView<T, D>,
+}
+
+// SAFETY: The guard provides only shared access to the elements.
+unsafe impl<T, D> Send for DetachedReadonlyArray<T, D>
+where
+    T: Element + Sync,
+    D: Dimension,
+{
+}
+
+unsafe impl<T, D> Sync for DetachedReadonlyArray<T, D>
+where
+    T: Element + Sync,
+    D: Dimension,
+{
+}
+
+impl<T, D> DetachedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    /// Provides an immutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array(&self) -> ArrayView<T, D> {
+        // SAFETY: The guard keeps the array alive and the borrow active.
+        unsafe { self.view.clone().deref_into_view() }
+    }
+
+    /// Provide an immutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice(&self) -> Result<&[T], NotContiguousError> {
+        let view = self.as_array();
+        let slice = view.as_slice_memory_order().ok_or(NotContiguousError)?;
+
+        // SAFETY: The guard keeps the array alive and the borrow active.
+        Ok(unsafe { from_raw_parts(slice.as_ptr(), slice.len()) })
+    }
+}
+
+impl<T, D> Drop for DetachedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn drop(&mut self) {
+        Python::with_gil(|py| release(py, self.array.as_ptr() as *mut PyArrayObject));
+    }
+}
+
+impl<T, D> fmt::Debug for DetachedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "DetachedReadonlyArray<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name).finish()
+    }
+}
+
+/// Shared handle to a read-only borrow of an array which is not bound to the GIL, cf. [`PyReadonlyArray::into_shared`].
+///
+/// Cloning the handle does not copy the array and the borrow is released when the last clone is dropped.
+/// The handle dereferences to [`DetachedReadonlyArray`] to access the array.
+pub struct SharedReadonlyArray<T, D>(Arc<DetachedReadonlyArray<T, D>>)
+where
+    T: Element,
+    D: Dimension;
+
+impl<T, D> Deref for SharedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    type Target = DetachedReadonlyArray<T, D>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T, D> Clone for SharedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T, D> fmt::Debug for SharedReadonlyArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "SharedReadonlyArray<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name).finish()
+    }
+}
+
+/// Read-write borrow of an array which is not bound to the GIL, cf. [`PyReadwriteArray::detach_view`].
+///
+/// This type is [`Send`] if the element type is [`Send`] and [`Sync`] if the element type is [`Sync`].
+/// Dropping it will acquire the GIL to release the borrow.
+pub struct DetachedReadwriteArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    array: Py<PyArray<T, D>>,
+    view: RawArrayViewMut<T, D>,
+}
+
+// SAFETY: The guard provides exclusive access to the elements only via `&mut self`.
+unsafe impl<T, D> Send for DetachedReadwriteArray<T, D>
+where
+    T: Element + Send,
+    D: Dimension,
+{
+}
+
+unsafe impl<T, D> Sync for DetachedReadwriteArray<T, D>
+where
+    T: Element + Sync,
+    D: Dimension,
+{
+}
+
+impl<T, D> DetachedReadwriteArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    /// Provides an immutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array(&self) -> ArrayView<T, D> {
+        // SAFETY: The guard keeps the array alive and the borrow active.
+        unsafe { self.view.clone().deref_into_view() }
+    }
+
+    /// Provide an immutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice(&self) -> Result<&[T], NotContiguousError> {
+        let view = self.as_array();
+        let slice = view.as_slice_memory_order().ok_or(NotContiguousError)?;
+
+        // SAFETY: The guard keeps the array alive and the borrow active.
+        Ok(unsafe { from_raw_parts(slice.as_ptr(), slice.len()) })
+    }
+
+    /// Provides a mutable array view of the interior of the NumPy array.
+    #[inline(always)]
+    pub fn as_array_mut(&mut self) -> ArrayViewMut<T, D> {
+        // SAFETY: The guard keeps the array alive and the exclusive borrow active.
+        unsafe { self.view.clone().deref_into_view_mut() }
+    }
+
+    /// Provide a mutable slice view of the interior of the NumPy array if it is contiguous.
+    #[inline(always)]
+    pub fn as_slice_mut(&mut self) -> Result<&mut [T], NotContiguousError> {
+        let mut view = self.as_array_mut();
+        let slice = view.as_slice_memory_order_mut().ok_or(NotContiguousError)?;
+
+        // SAFETY: The guard keeps the array alive and the exclusive borrow active.
+        Ok(unsafe { from_raw_parts_mut(slice.as_mut_ptr(), slice.len()) })
+    }
+}
+
+impl<T, D> Drop for DetachedReadwriteArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn drop(&mut self) {
+        Python::with_gil(|py| release_mut(py, self.array.as_ptr() as *mut PyArrayObject));
+    }
+}
+
+impl<T, D> fmt::Debug for DetachedReadwriteArray<T, D>
+where
+    T: Element,
+    D: Dimension,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = format!(
+            "DetachedReadwriteArray<{}, {}>",
+            type_name::<T>(),
+            type_name::<D>()
+        );
+
+        f.debug_struct(&name).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use pyo3::{types::IntoPyDict, Python};
+
+    use crate::array::PyArray1;
+
+    #[test]
+    fn test_debug_formatting() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (1, 2, 3), false);
+
+            {
+                let shared = array.readonly();
+
+                assert_eq!(
+                    format!("{:?}", shared),
+                    "PyReadonlyArray<f64, ndarray::dimension::dim::Dim<[usize; 3]>>"
+                );
+            }
+
+            {
+                let exclusive = array.readwrite();
+
+                assert_eq!(
+                    format!("{:?}", exclusive),
+                    "PyReadwriteArray<f64, ndarray::dimension::dim::Dim<[usize; 3]>>"
+                );
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "AlreadyBorrowed")]
+    fn cannot_clone_exclusive_borrow_via_deref() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (3, 2, 1), false);
+
+            let exclusive = array.readwrite();
+            let _shared = exclusive.clone();
+        });
+    }
+
+    #[test]
+    fn failed_resize_does_not_double_release() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, 10, false);
+
+            // The view will make the internal reference check of `PyArray_Resize` fail.
+            let locals = [("array", array)].into_py_dict(py);
+            let _view = py
+                .eval("array[:]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+
+            let exclusive = array.readwrite();
+            assert!(exclusive.resize(100).is_err());
+        });
+    }
+
+    #[test]
+    fn ineffective_resize_does_not_conflict() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, 10, false);
+
+            let exclusive = array.readwrite();
+            assert!(exclusive.resize(10).is_ok());
+        });
+    }
+}
@@ -0,0 +1,1985 @@
+//! The borrow flags shared by all extensions using this crate
+//!
+//! The borrow flags are owned by the first extension which needs them and are published
+//! as a [`PyCapsule`] in the `_RUST_NUMPY_BORROW_CHECKING_API` attribute of NumPy's
+//! `numpy.core.multiarray` module. The capsule contains a [`Shared`] table consisting of
+//! a version number, an opaque pointer to the borrow flags and the functions operating on them.
+//! Extensions compiled against different versions of this crate can thereby cooperate
+//! as long as the layout of the table is only ever extended in a backwards compatible manner.
+
+use std::cell::Cell;
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::ffi::CString;
+use std::hash::{Hash, Hasher};
+use std::iter::once;
+use std::mem::{forget, replace, take};
+use std::ops::Deref;
+use std::os::raw::{c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe, Location};
+use std::ptr::{null, null_mut};
+use std::slice::from_raw_parts;
+use std::str::from_utf8_unchecked;
+
+use ahash::{AHashMap, AHashSet};
+use num_integer::gcd;
+use pyo3::{exceptions::PyTypeError, ffi, types::PyCapsule, PyResult, Python, ToPyObject};
+
+use super::{BorrowInfo, BorrowKind, BorrowLocation};
+use crate::array::get_array_module;
+use crate::cold;
+use crate::error::BorrowError;
+use crate::npyffi::{self, PyArrayObject, NPY_ARRAY_WRITEABLE, NPY_ARRAY_WRITEBACKIFCOPY};
+
+/// The version of the [`Shared`] table created by this crate
+///
+/// Later versions may only append fields to the table
+/// and must keep the semantics of the existing ones unchanged.
+const VERSION: u64 = 1;
+
+/// The name of the module attribute containing the capsule
+const CAPSULE_ATTR: &str = "_RUST_NUMPY_BORROW_CHECKING_API";
+
+/// The fully qualified name of the capsule
+const CAPSULE_NAME: &str = "numpy.core.multiarray._RUST_NUMPY_BORROW_CHECKING_API";
+
+/// Option recording the locations at which borrows are acquired
+const DIAGNOSTICS: c_int = 1;
+
+/// Option clearing the `WRITEABLE` flag of arrays while they are borrowed shared
+/// and refusing exclusive borrows of arrays which are locked by Python
+const STRICT: c_int = 2;
+
+/// Return code indicating that the borrow checking API panicked internally
+///
+/// Panics are caught at the C ABI boundary and turned into this return code
+/// which the Rust wrappers below turn into panics on the calling side.
+const INTERNAL_ERROR: c_int = -3;
+
+/// The C-ABI function table contained in the capsule
+///
+/// All functions must be called with the GIL held.
+/// All functions returning `c_int` return [`INTERNAL_ERROR`] if they panicked.
+#[repr(C)]
+struct Shared {
+    /// the version of the table, at least [`VERSION`]
+    version: u64,
+    /// the opaque state passed to the functions below
+    flags: *mut c_void,
+    /// acquires a shared borrow, returning zero on success and `-1` if the array is already borrowed
+    ///
+    /// The location is recorded if diagnostics are enabled.
+    acquire: unsafe extern "C" fn(
+        flags: *mut c_void,
+        array: *mut PyArrayObject,
+        location: *const RawLocation,
+    ) -> c_int,
+    /// acquires an exclusive borrow, returning zero on success, `-1` if the array is already borrowed
+    /// and `-2` if strict mode is enabled and the array or one of its bases is not writeable
+    ///
+    /// The location is recorded if diagnostics are enabled.
+    acquire_mut: unsafe extern "C" fn(
+        flags: *mut c_void,
+        array: *mut PyArrayObject,
+        location: *const RawLocation,
+    ) -> c_int,
+    /// releases a shared borrow, returning zero on success
+    release: unsafe extern "C" fn(flags: *mut c_void, array: *mut PyArrayObject) -> c_int,
+    /// releases an exclusive borrow, returning zero on success
+    release_mut: unsafe extern "C" fn(flags: *mut c_void, array: *mut PyArrayObject) -> c_int,
+    /// sets the enabled options as a combination of [`DIAGNOSTICS`] and [`STRICT`]
+    ///
+    /// Disabling diagnostics discards all recorded locations.
+    set_options: unsafe extern "C" fn(flags: *mut c_void, options: c_int),
+    /// returns the enabled options
+    options: unsafe extern "C" fn(flags: *mut c_void) -> c_int,
+    /// calls `visitor` for all active borrows if `array` is null, otherwise only for the active borrows
+    /// which conflict with borrowing `array` exclusively if `exclusive` is non-zero or shared otherwise,
+    /// returning zero on success
+    visit: unsafe extern "C" fn(
+        flags: *mut c_void,
+        array: *mut PyArrayObject,
+        exclusive: c_int,
+        visitor: Visitor,
+        ctx: *mut c_void,
+    ) -> c_int,
+    /// permanently acquires a shared borrow, clears the `WRITEABLE` flag and leaks a reference to the array,
+    /// returning zero on success and `-1` if the array is already borrowed exclusively
+    ///
+    /// Freezing an array which is already frozen has no effect.
+    freeze: unsafe extern "C" fn(
+        flags: *mut c_void,
+        array: *mut PyArrayObject,
+        location: *const RawLocation,
+    ) -> c_int,
+    /// returns a non-zero value if the array was frozen
+    is_frozen: unsafe extern "C" fn(flags: *mut c_void, array: *mut PyArrayObject) -> c_int,
+}
+
+unsafe impl Send for Shared {}
+
+/// The location at which a borrow was acquired
+///
+/// A null `file` pointer indicates an unknown location.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct RawLocation {
+    file: *const u8,
+    file_len: usize,
+    line: u32,
+    column: u32,
+}
+
+impl RawLocation {
+    const UNKNOWN: Self = Self {
+        file: null(),
+        file_len: 0,
+        line: 0,
+        column: 0,
+    };
+
+    fn new(location: &'static Location<'static>) -> Self {
+        let file = location.file();
+
+        Self {
+            file: file.as_ptr(),
+            file_len: file.len(),
+            line: location.line(),
+            column: location.column(),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// The file name must be valid UTF-8 which is still allocated.
+    unsafe fn to_location(self) -> Option<BorrowLocation> {
+        if self.file.is_null() {
+            return None;
+        }
+
+        let file = from_utf8_unchecked(from_raw_parts(self.file, self.file_len));
+
+        Some(BorrowLocation {
+            file: file.to_owned(),
+            line: self.line,
+            column: self.column,
+        })
+    }
+}
+
+/// The description of an active borrow passed to a [`Visitor`]
+#[repr(C)]
+struct RawBorrowInfo {
+    /// the number of shared borrows if positive or `-1` for an exclusive borrow
+    readers: isize,
+    ndim: usize,
+    shape: *const usize,
+    strides: *const isize,
+    start: *const u8,
+    end: *const u8,
+    location: RawLocation,
+}
+
+/// Called for each visited borrow, a non-zero return value aborts the visit and is passed on to the caller
+type Visitor = unsafe extern "C" fn(ctx: *mut c_void, info: *const RawBorrowInfo) -> c_int;
+
+/// Runs `f` and turns panics into [`INTERNAL_ERROR`] so that they do not unwind across the C ABI.
+fn catch<F>(f: F) -> c_int
+where
+    F: FnOnce() -> c_int,
+{
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or(INTERNAL_ERROR)
+}
+
+/// Panics on the calling side after the borrow checking API returned an unexpected return code.
+#[cold]
+#[inline(never)]
+fn unexpected(rc: c_int) -> ! {
+    if rc == INTERNAL_ERROR {
+        panic!("Internal borrow checking API error");
+    }
+
+    panic!(
+        "Unexpected return code {} from borrow checking API, most likely a version mismatch",
+        rc
+    );
+}
+
+unsafe extern "C" fn acquire_shared(
+    flags: *mut c_void,
+    array: *mut PyArrayObject,
+    location: *const RawLocation,
+) -> c_int {
+    catch(|| {
+        // SAFETY: The GIL must be held when calling `acquire_shared`.
+        let py = Python::assume_gil_acquired();
+        let flags = &mut *(flags as *mut BorrowFlags);
+
+        let address = base_address(py, array);
+        let key = BorrowKey::from_array(array);
+
+        let lock_key = if flags.strict {
+            Some(key.clone())
+        } else {
+            None
+        };
+
+        if flags.acquire(address, key, location.as_ref()).is_err() {
+            return -1;
+        }
+
+        if let Some(key) = lock_key {
+            flags.lock(address, &key, array);
+        }
+
+        0
+    })
+}
+
+unsafe extern "C" fn acquire_mut_shared(
+    flags: *mut c_void,
+    array: *mut PyArrayObject,
+    location: *const RawLocation,
+) -> c_int {
+    catch(|| {
+        // SAFETY: The GIL must be held when calling `acquire_mut_shared`.
+        let py = Python::assume_gil_acquired();
+        let flags = &mut *(flags as *mut BorrowFlags);
+
+        let address = base_address(py, array);
+        let key = BorrowKey::from_array(array);
+
+        if flags.acquire_mut(address, key, location.as_ref()).is_err() {
+            return -1;
+        }
+
+        if flags.strict && is_locked(py, array) {
+            flags.release_mut(address, &BorrowKey::from_array(array));
+            return -2;
+        }
+
+        0
+    })
+}
+
+unsafe extern "C" fn release_shared(flags: *mut c_void, array: *mut PyArrayObject) -> c_int {
+    catch(|| {
+        // SAFETY: The GIL must be held when calling `release_shared`.
+        let py = Python::assume_gil_acquired();
+        let flags = &mut *(flags as *mut BorrowFlags);
+
+        let address = base_address(py, array);
+        let key = BorrowKey::from_array(array);
+
+        let locked = flags.release(address, &key);
+
+        unlock(locked);
+
+        0
+    })
+}
+
+unsafe extern "C" fn release_mut_shared(flags: *mut c_void, array: *mut PyArrayObject) -> c_int {
+    catch(|| {
+        // SAFETY: The GIL must be held when calling `release_mut_shared`.
+        let py = Python::assume_gil_acquired();
+        let flags = &mut *(flags as *mut BorrowFlags);
+
+        let address = base_address(py, array);
+        let key = BorrowKey::from_array(array);
+
+        flags.release_mut(address, &key);
+
+        0
+    })
+}
+
+unsafe extern "C" fn freeze_shared(
+    flags: *mut c_void,
+    array: *mut PyArrayObject,
+    location: *const RawLocation,
+) -> c_int {
+    catch(|| {
+        if (*(flags as *mut BorrowFlags)).frozen.contains(&array) {
+            return 0;
+        }
+
+        // The shared borrow is never released, thereby ruling out exclusive borrows of overlapping arrays.
+        if acquire_shared(flags, array, location) != 0 {
+            return -1;
+        }
+
+        // SAFETY: The GIL must be held when calling `freeze_shared`.
+        let py = Python::assume_gil_acquired();
+        let flags = &mut *(flags as *mut BorrowFlags);
+
+        // Keep the array alive so that its address cannot be reused by another array.
+        ffi::Py_INCREF(array as *mut ffi::PyObject);
+        flags.frozen.insert(array);
+
+        // Do not restore the flag when releasing shared borrows acquired in strict mode.
+        let address = base_address(py, array);
+        let key = BorrowKey::from_array(array);
+        flags.forget_lock(address, &key, array);
+
+        (*array).flags &= !NPY_ARRAY_WRITEABLE;
+
+        0
+    })
+}
+
+unsafe extern "C" fn is_frozen_shared(flags: *mut c_void, array: *mut PyArrayObject) -> c_int {
+    let flags = &*(flags as *mut BorrowFlags);
+
+    flags.frozen.contains(&array) as c_int
+}
+
+unsafe extern "C" fn set_options_shared(flags: *mut c_void, options: c_int) {
+    let flags = &mut *(flags as *mut BorrowFlags);
+
+    if options & DIAGNOSTICS != 0 {
+        flags.locations.get_or_insert_with(AHashMap::new);
+    } else {
+        flags.locations = None;
+    }
+
+    flags.strict = options & STRICT != 0;
+}
+
+unsafe extern "C" fn options_shared(flags: *mut c_void) -> c_int {
+    let flags = &*(flags as *mut BorrowFlags);
+
+    let mut options = 0;
+
+    if flags.locations.is_some() {
+        options |= DIAGNOSTICS;
+    }
+
+    if flags.strict {
+        options |= STRICT;
+    }
+
+    options
+}
+
+unsafe extern "C" fn visit_shared(
+    flags: *mut c_void,
+    array: *mut PyArrayObject,
+    exclusive: c_int,
+    visitor: Visitor,
+    ctx: *mut c_void,
+) -> c_int {
+    catch(|| {
+        // SAFETY: The GIL must be held when calling `visit_shared`.
+        let py = Python::assume_gil_acquired();
+        let flags = &*(flags as *mut BorrowFlags);
+
+        let filter = if array.is_null() {
+            None
+        } else {
+            Some((
+                base_address(py, array),
+                BorrowKey::from_array(array),
+                exclusive != 0,
+            ))
+        };
+
+        for (address, same_base_arrays) in &flags.flags {
+            for (key, readers) in same_base_arrays.iter() {
+                if let Some((filter_address, filter_key, exclusive)) = &filter {
+                    if filter_address != address
+                        || !(*exclusive || *readers < 0)
+                        || (filter_key != key && !filter_key.conflicts(key))
+                    {
+                        continue;
+                    }
+                }
+
+                let location = flags
+                    .locations
+                    .as_ref()
+                    .and_then(|locations| locations.get(address)?.get(key))
+                    .copied()
+                    .unwrap_or(RawLocation::UNKNOWN);
+
+                let (shape, strides): (Vec<_>, Vec<_>) = key.layout.iter().copied().unzip();
+
+                let info = RawBorrowInfo {
+                    readers: *readers,
+                    ndim: shape.len(),
+                    shape: shape.as_ptr(),
+                    strides: strides.as_ptr(),
+                    start: key.range.0,
+                    end: key.range.1,
+                    location,
+                };
+
+                let rc = visitor(ctx, &info);
+
+                if rc != 0 {
+                    return rc;
+                }
+            }
+        }
+
+        0
+    })
+}
+
+/// Caches a pointer to the [`Shared`] table similar to how the NumPy API is cached.
+struct SharedPtr(Cell<*const Shared>);
+
+unsafe impl Sync for SharedPtr {}
+
+static SHARED: SharedPtr = SharedPtr(Cell::new(null()));
+
+fn get_or_insert_shared(py: Python) -> PyResult<&'static Shared> {
+    let mut shared = SHARED.0.get();
+
+    if shared.is_null() {
+        shared = insert_shared(py)?;
+        SHARED.0.set(shared);
+    }
+
+    // SAFETY: The capsule containing the table is never destroyed, cf. `insert_shared`.
+    Ok(unsafe { &*shared })
+}
+
+#[cold]
+fn insert_shared(py: Python) -> PyResult<*const Shared> {
+    let module = get_array_module(py)?;
+
+    let capsule: &PyCapsule = match module.getattr(CAPSULE_ATTR) {
+        Ok(capsule) => capsule.downcast()?,
+        Err(_err) => {
+            let flags: *mut BorrowFlags = Box::into_raw(Box::default());
+
+            let shared = Shared {
+                version: VERSION,
+                flags: flags as *mut c_void,
+                acquire: acquire_shared,
+                acquire_mut: acquire_mut_shared,
+                release: release_shared,
+                release_mut: release_mut_shared,
+                set_options: set_options_shared,
+                options: options_shared,
+                visit: visit_shared,
+                freeze: freeze_shared,
+                is_frozen: is_frozen_shared,
+            };
+
+            let capsule = PyCapsule::new_with_destructor(
+                py,
+                shared,
+                &CString::new(CAPSULE_NAME).unwrap(),
+                |shared, _ctx| {
+                    // SAFETY: `shared.flags` was initialized using `Box::into_raw`.
+                    let _ = unsafe { Box::from_raw(shared.flags as *mut BorrowFlags) };
+                },
+            )?;
+            module.setattr(CAPSULE_ATTR, capsule)?;
+            capsule
+        }
+    };
+
+    if capsule.name().to_bytes() != CAPSULE_NAME.as_bytes() {
+        return Err(PyTypeError::new_err(format!(
+            "Attribute {} of NumPy's multiarray module is not a borrow checking capsule",
+            CAPSULE_ATTR
+        )));
+    }
+
+    // All versions of the table start with the version field.
+    let version = unsafe { *(capsule.pointer() as *const u64) };
+    if version < VERSION {
+        return Err(PyTypeError::new_err(format!(
+            "Version {} of the borrow checking API is not supported by this version of rust-numpy",
+            version
+        )));
+    }
+
+    let shared = capsule.pointer() as *const Shared;
+
+    // Intentionally leak a reference to the capsule so that the cached pointer into its interior stays valid.
+    forget(capsule.to_object(py));
+
+    Ok(shared)
+}
+
+pub(crate) fn acquire(
+    py: Python,
+    array: *mut PyArrayObject,
+    location: &'static Location<'static>,
+) -> Result<(), BorrowError> {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    let location = RawLocation::new(location);
+
+    let rc = unsafe { (shared.acquire)(shared.flags, array, &location) };
+
+    match rc {
+        0 => Ok(()),
+        -1 => Err(already_borrowed(shared, array, false)),
+        rc => unexpected(rc),
+    }
+}
+
+pub(crate) fn acquire_mut(
+    py: Python,
+    array: *mut PyArrayObject,
+    location: &'static Location<'static>,
+) -> Result<(), BorrowError> {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    let location = RawLocation::new(location);
+
+    let rc = unsafe { (shared.acquire_mut)(shared.flags, array, &location) };
+
+    match rc {
+        0 => Ok(()),
+        -1 => Err(already_borrowed(shared, array, true)),
+        -2 => Err(BorrowError::NotWriteable),
+        rc => unexpected(rc),
+    }
+}
+
+pub(crate) fn freeze(
+    py: Python,
+    array: *mut PyArrayObject,
+    location: &'static Location<'static>,
+) -> Result<(), BorrowError> {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    let location = RawLocation::new(location);
+
+    let rc = unsafe { (shared.freeze)(shared.flags, array, &location) };
+
+    match rc {
+        0 => Ok(()),
+        -1 => Err(already_borrowed(shared, array, false)),
+        rc => unexpected(rc),
+    }
+}
+
+pub(crate) fn is_frozen(py: Python, array: *mut PyArrayObject) -> bool {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    unsafe { (shared.is_frozen)(shared.flags, array) != 0 }
+}
+
+pub(crate) fn release(py: Python, array: *mut PyArrayObject) {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    let rc = unsafe { (shared.release)(shared.flags, array) };
+
+    if rc != 0 {
+        unexpected(rc);
+    }
+}
+
+pub(crate) fn release_mut(py: Python, array: *mut PyArrayObject) {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    let rc = unsafe { (shared.release_mut)(shared.flags, array) };
+
+    if rc != 0 {
+        unexpected(rc);
+    }
+}
+
+#[cold]
+fn already_borrowed(shared: &Shared, array: *mut PyArrayObject, exclusive: bool) -> BorrowError {
+    if unsafe { (shared.options)(shared.flags) } & DIAGNOSTICS == 0 {
+        return BorrowError::AlreadyBorrowed;
+    }
+
+    match visit(shared, array, exclusive).into_iter().next() {
+        Some(info) => BorrowError::AlreadyBorrowedBy(Box::new(info)),
+        None => BorrowError::AlreadyBorrowed,
+    }
+}
+
+pub(super) fn set_diagnostics(py: Python, enabled: bool) {
+    set_option(py, DIAGNOSTICS, enabled);
+}
+
+pub(super) fn set_strict(py: Python, enabled: bool) {
+    set_option(py, STRICT, enabled);
+}
+
+fn set_option(py: Python, option: c_int, enabled: bool) {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    unsafe {
+        let mut options = (shared.options)(shared.flags);
+
+        if enabled {
+            options |= option;
+        } else {
+            options &= !option;
+        }
+
+        (shared.set_options)(shared.flags, options);
+    }
+}
+
+pub(super) fn active_borrows(py: Python) -> Vec<BorrowInfo> {
+    let shared = get_or_insert_shared(py).expect("Internal borrow checking API error");
+
+    visit(shared, null_mut(), false)
+}
+
+fn visit(shared: &Shared, array: *mut PyArrayObject, exclusive: bool) -> Vec<BorrowInfo> {
+    unsafe extern "C" fn collect(ctx: *mut c_void, info: *const RawBorrowInfo) -> c_int {
+        catch(|| collect_info(&mut *(ctx as *mut Vec<BorrowInfo>), &*info))
+    }
+
+    unsafe fn collect_info(infos: &mut Vec<BorrowInfo>, info: &RawBorrowInfo) -> c_int {
+        let (kind, count) = if info.readers < 0 {
+            (BorrowKind::Exclusive, 1)
+        } else {
+            (BorrowKind::Shared, info.readers as usize)
+        };
+
+        let (shape, strides) = if info.ndim != 0 {
+            (
+                from_raw_parts(info.shape, info.ndim).to_vec(),
+                from_raw_parts(info.strides, info.ndim).to_vec(),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        infos.push(BorrowInfo {
+            kind,
+            count,
+            shape,
+            strides,
+            data_range: info.start as usize..info.end as usize,
+            location: info.location.to_location(),
+        });
+
+        0
+    }
+
+    let mut infos = Vec::new();
+
+    let rc = unsafe {
+        (shared.visit)(
+            shared.flags,
+            array,
+            exclusive as c_int,
+            collect,
+            &mut infos as *mut Vec<BorrowInfo> as *mut c_void,
+        )
+    };
+
+    if rc != 0 {
+        unexpected(rc);
+    }
+
+    infos
+}
+
+#[derive(Default)]
+struct BorrowFlags {
+    flags: AHashMap<*mut u8, SameBaseArrays>,
+    /// the locations at which borrows were first acquired if diagnostics are enabled
+    locations: Option<AHashMap<*mut u8, AHashMap<BorrowKey, RawLocation>>>,
+    /// whether strict mode is enabled
+    strict: bool,
+    /// the arrays which were frozen and are kept alive indefinitely
+    frozen: AHashSet<*mut PyArrayObject>,
+}
+
+impl BorrowFlags {
+    /// Clears the `WRITEABLE` flag of the array until all shared borrows with the given key are released.
+    ///
+    /// The lock is recorded in the borrow itself so that releasing borrows which were acquired
+    /// before strict mode was enabled does not restore the flag while other borrows are still active.
+    ///
+    /// # Safety
+    ///
+    /// The array must be alive, shared borrowed with the given key and the GIL must be held.
+    unsafe fn lock(&mut self, address: *mut u8, key: &BorrowKey, array: *mut PyArrayObject) {
+        if (*array).flags & NPY_ARRAY_WRITEABLE == 0 {
+            return;
+        }
+
+        let borrow = self
+            .flags
+            .get_mut(&address)
+            .and_then(|same_base_arrays| same_base_arrays.find_mut(key))
+            .unwrap();
+
+        (*array).flags &= !NPY_ARRAY_WRITEABLE;
+
+        // Keep the array alive so that its flag can be restored when the borrow is released.
+        ffi::Py_INCREF(array as *mut ffi::PyObject);
+        borrow.locked.push(array);
+    }
+
+    /// Removes the array from the arrays locked by the borrow with the given key without restoring its flag.
+    ///
+    /// # Safety
+    ///
+    /// The array must be kept alive by another reference and the GIL must be held.
+    unsafe fn forget_lock(&mut self, address: *mut u8, key: &BorrowKey, array: *mut PyArrayObject) {
+        let borrow = self
+            .flags
+            .get_mut(&address)
+            .and_then(|same_base_arrays| same_base_arrays.find_mut(key))
+            .unwrap();
+
+        if let Some(idx) = borrow.locked.iter().position(|locked| *locked == array) {
+            borrow.locked.swap_remove(idx);
+            ffi::Py_DECREF(array as *mut ffi::PyObject);
+        }
+    }
+
+    fn acquire(
+        &mut self,
+        address: *mut u8,
+        key: BorrowKey,
+        location: Option<&RawLocation>,
+    ) -> Result<(), ()> {
+        let borrow_flags = &mut self.flags;
+
+        match borrow_flags.entry(address) {
+            Entry::Occupied(entry) => {
+                let same_base_arrays = entry.into_mut();
+
+                if let Some(readers) = same_base_arrays.get_mut(&key) {
+                    // Zero flags are removed during release.
+                    assert_ne!(*readers, 0);
+
+                    let new_readers = readers.wrapping_add(1);
+
+                    if new_readers <= 0 {
+                        cold();
+                        return Err(());
+                    }
+
+                    *readers = new_readers;
+                } else {
+                    if same_base_arrays
+                        .iter()
+                        .any(|(other, readers)| *readers < 0 && key.conflicts(other))
+                    {
+                        cold();
+                        return Err(());
+                    }
+
+                    record_location(&mut self.locations, address, &key, location);
+                    same_base_arrays.push(key, 1);
+                }
+            }
+            Entry::Vacant(entry) => {
+                record_location(&mut self.locations, address, &key, location);
+                entry.insert(SameBaseArrays::new(key, 1));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Releases a shared borrow and returns the arrays locked by it if it was the last one with the given key.
+    #[must_use]
+    fn release(&mut self, address: *mut u8, key: &BorrowKey) -> Vec<*mut PyArrayObject> {
+        let mut entry = match self.flags.entry(address) {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(_) => unreachable!("Released an array which is not borrowed"),
+        };
+
+        let readers = entry.get_mut().get_mut(key).unwrap();
+
+        *readers -= 1;
+
+        if *readers != 0 {
+            return Vec::new();
+        }
+
+        forget_location(&mut self.locations, address, key);
+
+        let (locked, last) = entry.get_mut().remove(key);
+
+        // The entry is removed without hashing the address again.
+        if last {
+            entry.remove();
+        }
+
+        locked
+    }
+
+    fn acquire_mut(
+        &mut self,
+        address: *mut u8,
+        key: BorrowKey,
+        location: Option<&RawLocation>,
+    ) -> Result<(), ()> {
+        let borrow_flags = &mut self.flags;
+
+        match borrow_flags.entry(address) {
+            Entry::Occupied(entry) => {
+                let same_base_arrays = entry.into_mut();
+
+                if let Some(writers) = same_base_arrays.get_mut(&key) {
+                    // Zero flags are removed during release.
+                    assert_ne!(*writers, 0);
+
+                    cold();
+                    return Err(());
+                } else {
+                    if same_base_arrays
+                        .iter()
+                        .any(|(other, writers)| *writers != 0 && key.conflicts(other))
+                    {
+                        cold();
+                        return Err(());
+                    }
+
+                    record_location(&mut self.locations, address, &key, location);
+                    same_base_arrays.push(key, -1);
+                }
+            }
+            Entry::Vacant(entry) => {
+                record_location(&mut self.locations, address, &key, location);
+                entry.insert(SameBaseArrays::new(key, -1));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn release_mut(&mut self, address: *mut u8, key: &BorrowKey) {
+        let mut entry = match self.flags.entry(address) {
+            Entry::Occupied(entry) => entry,
+            Entry::Vacant(_) => unreachable!("Released an array which is not borrowed"),
+        };
+
+        forget_location(&mut self.locations, address, key);
+
+        // The entry is removed without hashing the address again.
+        if entry.get_mut().remove(key).1 {
+            entry.remove();
+        }
+    }
+}
+
+/// Restores the `WRITEABLE` flag of the given arrays and drops the references kept by [`BorrowFlags::lock`].
+///
+/// # Safety
+///
+/// The GIL must be held and the borrow flags must not be borrowed as dropping the arrays can release further borrows.
+unsafe fn unlock(locked: Vec<*mut PyArrayObject>) {
+    for array in locked {
+        (*array).flags |= NPY_ARRAY_WRITEABLE;
+        ffi::Py_DECREF(array as *mut ffi::PyObject);
+    }
+}
+
+/// The borrows of arrays sharing the same base address
+///
+/// The first borrow is stored inline as most base addresses are only borrowed via a single array at any time,
+/// further borrows are searched linearly as the conflict check needs to visit all of them anyway.
+struct SameBaseArrays {
+    first: Borrow,
+    rest: Vec<Borrow>,
+}
+
+/// The borrows of arrays with the same key
+struct Borrow {
+    key: BorrowKey,
+    /// the number of shared borrows if positive or `-1` for an exclusive borrow
+    flag: isize,
+    /// the arrays whose `WRITEABLE` flag was cleared in strict mode while shared borrowed with this key
+    locked: Vec<*mut PyArrayObject>,
+}
+
+impl Borrow {
+    fn new(key: BorrowKey, flag: isize) -> Self {
+        Self {
+            key,
+            flag,
+            locked: Vec::new(),
+        }
+    }
+}
+
+impl SameBaseArrays {
+    fn new(key: BorrowKey, flag: isize) -> Self {
+        Self {
+            first: Borrow::new(key, flag),
+            rest: Vec::new(),
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&BorrowKey, &isize)> {
+        once(&self.first)
+            .chain(&self.rest)
+            .map(|borrow| (&borrow.key, &borrow.flag))
+    }
+
+    fn find_mut(&mut self, key: &BorrowKey) -> Option<&mut Borrow> {
+        if self.first.key == *key {
+            return Some(&mut self.first);
+        }
+
+        self.rest.iter_mut().find(|borrow| borrow.key == *key)
+    }
+
+    fn get_mut(&mut self, key: &BorrowKey) -> Option<&mut isize> {
+        self.find_mut(key).map(|borrow| &mut borrow.flag)
+    }
+
+    fn push(&mut self, key: BorrowKey, flag: isize) {
+        self.rest.push(Borrow::new(key, flag));
+    }
+
+    /// Removes the borrow and returns the arrays locked by it and whether it was the last one.
+    fn remove(&mut self, key: &BorrowKey) -> (Vec<*mut PyArrayObject>, bool) {
+        if self.first.key == *key {
+            match self.rest.pop() {
+                Some(last) => (replace(&mut self.first, last).locked, false),
+                None => (take(&mut self.first.locked), true),
+            }
+        } else {
+            let idx = self
+                .rest
+                .iter()
+                .position(|borrow| borrow.key == *key)
+                .unwrap();
+
+            (self.rest.swap_remove(idx).locked, false)
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct BorrowKey {
+    /// exclusive range of lowest and highest address covered by array
+    range: (*mut u8, *mut u8),
+    /// the data address on which address computations are based
+    data_ptr: *mut u8,
+    /// the dimensions and strides in bytes of the array
+    layout: KeyLayout,
+    /// the size of the elements of the array in bytes
+    itemsize: isize,
+}
+
+impl BorrowKey {
+    fn from_array(array: *mut PyArrayObject) -> Self {
+        let range = data_range(array);
+
+        let (shape, strides, itemsize, data_ptr) = unsafe { layout(array) };
+
+        let layout = KeyLayout::new(shape, strides);
+
+        Self {
+            range,
+            data_ptr,
+            layout,
+            itemsize,
+        }
+    }
+
+    fn conflicts(&self, other: &Self) -> bool {
+        debug_assert!(self.range.0 <= self.range.1);
+        debug_assert!(other.range.0 <= other.range.1);
+
+        if other.range.0 >= self.range.1 || self.range.0 >= other.range.1 {
+            return false;
+        }
+
+        // The arrays share an element if and only if the bounded Diophantine equation which combines their data pointers,
+        // indices and strides has a solution. Solving this exactly can require exponential work in the number of dimensions,
+        // hence a conflict is assumed if the search is aborted as that is the safe choice.
+        //
+        // https://users.rust-lang.org/t/math-for-borrow-checking-numpy-arrays/73303
+        AliasingProblem::new(self, other).map_or(false, |problem| problem.solve().unwrap_or(true))
+    }
+}
+
+/// The maximum number of dimensions for which a [`KeyLayout`] is stored inline
+const INLINE_DIMS: usize = 4;
+
+/// The dimensions and strides in bytes of an array
+///
+/// Layouts of up to [`INLINE_DIMS`] dimensions are stored inline so that computing
+/// the key of an array when acquiring or releasing a borrow does not allocate.
+#[derive(Clone)]
+enum KeyLayout {
+    Inline(usize, [(usize, isize); INLINE_DIMS]),
+    Heap(Box<[(usize, isize)]>),
+}
+
+impl KeyLayout {
+    fn new(shape: &[usize], strides: &[isize]) -> Self {
+        let dims = shape.iter().copied().zip(strides.iter().copied());
+
+        if shape.len() <= INLINE_DIMS {
+            let mut inline = [(0, 0); INLINE_DIMS];
+
+            for (slot, dim) in inline.iter_mut().zip(dims) {
+                *slot = dim;
+            }
+
+            Self::Inline(shape.len(), inline)
+        } else {
+            Self::Heap(dims.collect())
+        }
+    }
+}
+
+impl Deref for KeyLayout {
+    type Target = [(usize, isize)];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Inline(len, inline) => &inline[..*len],
+            Self::Heap(heap) => heap,
+        }
+    }
+}
+
+impl PartialEq for KeyLayout {
+    fn eq(&self, other: &Self) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for KeyLayout {}
+
+impl Hash for KeyLayout {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        (**self).hash(state);
+    }
+}
+
+/// The bounded aliasing problem of two arrays in the form `sum(coeff * x) = rhs` with `0 <= x <= bound`.
+///
+/// This follows the approach of NumPy's `solve_may_share_memory`.
+struct AliasingProblem {
+    /// the positive coefficients and upper bounds of the terms ordered by descending coefficients
+    terms: Vec<(isize, isize)>,
+    /// the right-hand side of the equation
+    rhs: isize,
+    /// the maximum value and the GCD of the coefficients of the terms starting at each index
+    suffixes: Vec<(isize, isize)>,
+    /// the number of steps remaining before the search is aborted
+    work: usize,
+}
+
+impl AliasingProblem {
+    /// Maximum number of steps taken before a conflict is assumed
+    const MAX_WORK: usize = 1 << 12;
+
+    /// Returns `None` if either array is empty and hence the arrays cannot share any elements.
+    fn new(lhs: &BorrowKey, rhs: &BorrowKey) -> Option<Self> {
+        // An element `x` of `lhs` overlaps with an element `y` of `rhs` if and only if
+        // `x - y + rhs.itemsize - 1` is in the range `0..=lhs.itemsize + rhs.itemsize - 2`
+        // which is represented by the additional term with unit coefficient.
+        let mut terms = Vec::with_capacity(lhs.layout.len() + rhs.layout.len() + 1);
+
+        let mut rhs_value = unsafe { rhs.data_ptr.offset_from(lhs.data_ptr) } + rhs.itemsize - 1;
+
+        for &(dim, stride) in lhs.layout.iter() {
+            terms.push((stride, dim.checked_sub(1)? as isize));
+        }
+
+        for &(dim, stride) in rhs.layout.iter() {
+            terms.push((-stride, dim.checked_sub(1)? as isize));
+        }
+
+        terms.push((1, lhs.itemsize + rhs.itemsize - 2));
+
+        // Negative coefficients are made positive by substituting `bound - x` for `x`.
+        for (coeff, bound) in &mut terms {
+            if *coeff < 0 {
+                *coeff = -*coeff;
+                rhs_value += *coeff * *bound;
+            }
+        }
+
+        terms.retain(|&(coeff, bound)| coeff != 0 && bound != 0);
+
+        // Terms with equal coefficients are merged as their sums cover all values up to the sum of their bounds.
+        terms.sort_unstable_by_key(|&(coeff, _)| Reverse(coeff));
+
+        terms.dedup_by(|next, prev| {
+            if next.0 == prev.0 {
+                prev.1 += next.1;
+                true
+            } else {
+                false
+            }
+        });
+
+        let mut suffixes = vec![(0, 0); terms.len() + 1];
+
+        for (idx, &(coeff, bound)) in terms.iter().enumerate().rev() {
+            let (max, gcd_coeffs) = suffixes[idx + 1];
+            suffixes[idx] = (max + coeff * bound, gcd(gcd_coeffs, coeff));
+        }
+
+        Some(Self {
+            terms,
+            rhs: rhs_value,
+            suffixes,
+            work: Self::MAX_WORK,
+        })
+    }
+
+    /// Returns `None` if the search was aborted.
+    fn solve(mut self) -> Option<bool> {
+        let rhs = self.rhs;
+        self.search(0, rhs)
+    }
+
+    fn search(&mut self, idx: usize, rhs: isize) -> Option<bool> {
+        self.work = self.work.checked_sub(1)?;
+
+        let (max, gcd_coeffs) = self.suffixes[idx];
+
+        if rhs < 0 || rhs > max {
+            return Some(false);
+        }
+
+        if idx == self.terms.len() {
+            return Some(rhs == 0);
+        }
+
+        if rhs % gcd_coeffs != 0 {
+            return Some(false);
+        }
+
+        let (coeff, bound) = self.terms[idx];
+        let (rest_max, rest_gcd) = self.suffixes[idx + 1];
+
+        if rest_gcd == 0 {
+            // This is the last term and `coeff` divides `rhs` which is at most `coeff * bound`.
+            return Some(true);
+        }
+
+        let mut lower = if rhs > rest_max {
+            (rhs - rest_max + coeff - 1) / coeff
+        } else {
+            0
+        };
+        let upper = bound.min(rhs / coeff);
+
+        // The remaining terms can only represent multiples of `rest_gcd`, hence only every `step`-th value needs to be considered.
+        let step = rest_gcd / gcd(coeff, rest_gcd);
+
+        if step > 1 {
+            let first = congruence(coeff, rhs, rest_gcd);
+            lower += (first - lower % step + step) % step;
+        }
+
+        let mut value = lower;
+
+        while value <= upper {
+            if self.search(idx + 1, rhs - coeff * value)? {
+                return Some(true);
+            }
+
+            value += step;
+        }
+
+        Some(false)
+    }
+}
+
+/// Computes the smallest non-negative `x` with `coeff * x = rhs (mod modulus)`,
+/// assuming that `gcd(coeff, modulus)` divides `rhs`.
+fn congruence(coeff: isize, rhs: isize, modulus: isize) -> isize {
+    let divisor = gcd(coeff, modulus);
+
+    let (coeff, rhs, modulus) = (coeff / divisor, rhs / divisor, modulus / divisor);
+
+    // Extended Euclidean algorithm to compute the inverse of `coeff` modulo `modulus`
+    let (mut r0, mut r1) = (coeff.rem_euclid(modulus), modulus);
+    let (mut s0, mut s1) = (1, 0);
+
+    while r1 != 0 {
+        let quotient = r0 / r1;
+        let tmp = r0 - quotient * r1;
+        r0 = r1;
+        r1 = tmp;
+        let tmp = s0 - quotient * s1;
+        s0 = s1;
+        s1 = tmp;
+    }
+
+    (s0.rem_euclid(modulus) * rhs.rem_euclid(modulus)) % modulus
+}
+
+fn record_location(
+    locations: &mut Option<AHashMap<*mut u8, AHashMap<BorrowKey, RawLocation>>>,
+    address: *mut u8,
+    key: &BorrowKey,
+    location: Option<&RawLocation>,
+) {
+    if let (Some(locations), Some(location)) = (locations, location) {
+        locations
+            .entry(address)
+            .or_default()
+            .insert(key.clone(), *location);
+    }
+}
+
+fn forget_location(
+    locations: &mut Option<AHashMap<*mut u8, AHashMap<BorrowKey, RawLocation>>>,
+    address: *mut u8,
+    key: &BorrowKey,
+) {
+    if let Some(locations) = locations {
+        if let Entry::Occupied(mut entry) = locations.entry(address) {
+            entry.get_mut().remove(key);
+
+            if entry.get().is_empty() {
+                entry.remove();
+            }
+        }
+    }
+}
+
+/// Checks whether the array or one of its bases is not writeable.
+///
+/// The bases of writeback-if-copy arrays are not considered as NumPy locks them until the copy is written back.
+fn is_locked(py: Python, mut array: *mut PyArrayObject) -> bool {
+    loop {
+        let flags = unsafe { (*array).flags };
+
+        if flags & NPY_ARRAY_WRITEABLE == 0 {
+            return true;
+        }
+
+        if flags & NPY_ARRAY_WRITEBACKIFCOPY != 0 {
+            return false;
+        }
+
+        let base = unsafe { (*array).base };
+
+        if !base.is_null() && unsafe { npyffi::PyArray_Check(py, base) } != 0 {
+            array = base as *mut PyArrayObject;
+        } else {
+            return false;
+        }
+    }
+}
+
+fn base_address(py: Python, mut array: *mut PyArrayObject) -> *mut u8 {
+    loop {
+        let base = unsafe { (*array).base };
+
+        if base.is_null() {
+            return array as *mut u8;
+        } else if unsafe { npyffi::PyArray_Check(py, base) } != 0 {
+            array = base as *mut PyArrayObject;
+        } else {
+            return base as *mut u8;
+        }
+    }
+}
+
+/// Reads the shape, strides in bytes, item size and data pointer of the given array.
+unsafe fn layout<'a>(array: *mut PyArrayObject) -> (&'a [usize], &'a [isize], isize, *mut u8) {
+    let nd = (*array).nd as usize;
+    let itemsize = (*(*array).descr).elsize as isize;
+    let data = (*array).data as *mut u8;
+
+    if nd == 0 {
+        return (&[], &[], itemsize, data);
+    }
+
+    let shape = std::slice::from_raw_parts((*array).dimensions as *const usize, nd);
+    let strides = std::slice::from_raw_parts((*array).strides, nd);
+
+    (shape, strides, itemsize, data)
+}
+
+fn data_range(array: *mut PyArrayObject) -> (*mut u8, *mut u8) {
+    let (shape, strides, itemsize, data) = unsafe { layout(array) };
+
+    let mut start = 0;
+    let mut end = 0;
+
+    if shape.iter().all(|dim| *dim != 0) {
+        for (&dim, &stride) in shape.iter().zip(strides) {
+            let offset = (dim - 1) as isize * stride;
+
+            if offset >= 0 {
+                end += offset;
+            } else {
+                start += offset;
+            }
+        }
+
+        end += itemsize;
+    }
+
+    let start = unsafe { data.offset(start) };
+    let end = unsafe { data.offset(end) };
+
+    (start, end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ops::Index;
+
+    use ndarray::Array;
+    use pyo3::types::IntoPyDict;
+
+    use crate::array::{PyArray, PyArray1, PyArray2, PyArray3};
+    use crate::convert::IntoPyArray;
+
+    impl SameBaseArrays {
+        fn len(&self) -> usize {
+            1 + self.rest.len()
+        }
+
+        fn contains_key(&self, key: &BorrowKey) -> bool {
+            self.iter().any(|(other, _)| other == key)
+        }
+    }
+
+    impl Index<&BorrowKey> for SameBaseArrays {
+        type Output = isize;
+
+        fn index(&self, key: &BorrowKey) -> &isize {
+            self.iter()
+                .find(|(other, _)| *other == key)
+                .map(|(_, flag)| flag)
+                .unwrap()
+        }
+    }
+
+    fn get_borrow_flags<'py>(py: Python<'py>) -> &'py AHashMap<*mut u8, SameBaseArrays> {
+        let shared = get_or_insert_shared(py).unwrap();
+        assert_eq!(shared.version, VERSION);
+        unsafe { &(*(shared.flags as *mut BorrowFlags)).flags }
+    }
+
+    #[test]
+    fn without_base_object() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (1, 2, 3), false);
+
+            let base = unsafe { (*array.as_array_ptr()).base };
+            assert!(base.is_null());
+
+            let base_address = base_address(py, array.as_array_ptr());
+            assert_eq!(base_address, array as *const _ as *mut u8);
+
+            let data_range = data_range(array.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, unsafe { array.data().add(6) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn with_base_object() {
+        Python::with_gil(|py| {
+            let array = Array::<f64, _>::zeros((1, 2, 3)).into_pyarray(py);
+
+            let base = unsafe { (*array.as_array_ptr()).base };
+            assert!(!base.is_null());
+
+            let base_address = base_address(py, array.as_array_ptr());
+            assert_ne!(base_address, array as *const _ as *mut u8);
+            assert_eq!(base_address, base as *mut u8);
+
+            let data_range = data_range(array.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, unsafe { array.data().add(6) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn view_without_base_object() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (1, 2, 3), false);
+
+            let locals = [("array", array)].into_py_dict(py);
+            let view = py
+                .eval("array[:,:,0]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+            assert_ne!(view as *const _ as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*view.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base_address = base_address(py, view.as_array_ptr());
+            assert_ne!(base_address, view as *const _ as *mut u8);
+            assert_eq!(base_address, base as *mut u8);
+
+            let data_range = data_range(view.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, unsafe { array.data().add(4) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn view_with_base_object() {
+        Python::with_gil(|py| {
+            let array = Array::<f64, _>::zeros((1, 2, 3)).into_pyarray(py);
+
+            let locals = [("array", array)].into_py_dict(py);
+            let view = py
+                .eval("array[:,:,0]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+            assert_ne!(view as *const _ as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*view.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*array.as_array_ptr()).base };
+            assert!(!base.is_null());
+
+            let base_address = base_address(py, view.as_array_ptr());
+            assert_ne!(base_address, view as *const _ as *mut u8);
+            assert_ne!(base_address, array as *const _ as *mut u8);
+            assert_eq!(base_address, base as *mut u8);
+
+            let data_range = data_range(view.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, unsafe { array.data().add(4) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn view_of_view_without_base_object() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (1, 2, 3), false);
+
+            let locals = [("array", array)].into_py_dict(py);
+            let view1 = py
+                .eval("array[:,:,0]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+            assert_ne!(view1 as *const _ as *mut u8, array as *const _ as *mut u8);
+
+            let locals = [("view1", view1)].into_py_dict(py);
+            let view2 = py
+                .eval("view1[:,0]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+            assert_ne!(view2 as *const _ as *mut u8, array as *const _ as *mut u8);
+            assert_ne!(view2 as *const _ as *mut u8, view1 as *const _ as *mut u8);
+
+            let base = unsafe { (*view2.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*view1.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base_address = base_address(py, view2.as_array_ptr());
+            assert_ne!(base_address, view2 as *const _ as *mut u8);
+            assert_ne!(base_address, view1 as *const _ as *mut u8);
+            assert_eq!(base_address, base as *mut u8);
+
+            let data_range = data_range(view2.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, unsafe { array.data().add(1) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn view_of_view_with_base_object() {
+        Python::with_gil(|py| {
+            let array = Array::<f64, _>::zeros((1, 2, 3)).into_pyarray(py);
+
+            let locals = [("array", array)].into_py_dict(py);
+            let view1 = py
+                .eval("array[:,:,0]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+            assert_ne!(view1 as *const _ as *mut u8, array as *const _ as *mut u8);
+
+            let locals = [("view1", view1)].into_py_dict(py);
+            let view2 = py
+                .eval("view1[:,0]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+            assert_ne!(view2 as *const _ as *mut u8, array as *const _ as *mut u8);
+            assert_ne!(view2 as *const _ as *mut u8, view1 as *const _ as *mut u8);
+
+            let base = unsafe { (*view2.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*view1.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*array.as_array_ptr()).base };
+            assert!(!base.is_null());
+
+            let base_address = base_address(py, view2.as_array_ptr());
+            assert_ne!(base_address, view2 as *const _ as *mut u8);
+            assert_ne!(base_address, view1 as *const _ as *mut u8);
+            assert_ne!(base_address, array as *const _ as *mut u8);
+            assert_eq!(base_address, base as *mut u8);
+
+            let data_range = data_range(view2.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, unsafe { array.data().add(1) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn view_with_negative_strides() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (1, 2, 3), false);
+
+            let locals = [("array", array)].into_py_dict(py);
+            let view = py
+                .eval("array[::-1,:,::-1]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray3<f64>>()
+                .unwrap();
+            assert_ne!(view as *const _ as *mut u8, array as *const _ as *mut u8);
+
+            let base = unsafe { (*view.as_array_ptr()).base };
+            assert_eq!(base as *mut u8, array as *const _ as *mut u8);
+
+            let base_address = base_address(py, view.as_array_ptr());
+            assert_ne!(base_address, view as *const _ as *mut u8);
+            assert_eq!(base_address, base as *mut u8);
+
+            let data_range = data_range(view.as_array_ptr());
+            assert_eq!(view.data(), unsafe { array.data().offset(2) });
+            assert_eq!(data_range.0, unsafe { view.data().offset(-2) } as *mut u8);
+            assert_eq!(data_range.1, unsafe { view.data().offset(4) } as *mut u8);
+        });
+    }
+
+    #[test]
+    fn array_with_zero_dimensions() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (1, 0, 3), false);
+
+            let base = unsafe { (*array.as_array_ptr()).base };
+            assert!(base.is_null());
+
+            let base_address = base_address(py, array.as_array_ptr());
+            assert_eq!(base_address, array as *const _ as *mut u8);
+
+            let data_range = data_range(array.as_array_ptr());
+            assert_eq!(data_range.0, array.data() as *mut u8);
+            assert_eq!(data_range.1, array.data() as *mut u8);
+        });
+    }
+
+    #[test]
+    fn view_with_non_dividing_strides() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, (10, 10), false);
+            let locals = [("array", array)].into_py_dict(py);
+
+            let view1 = py
+                .eval("array[:,::3]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+
+            let key1 = BorrowKey::from_array(view1.as_array_ptr());
+
+            assert_eq!(view1.strides(), &[80, 24]);
+
+            let view2 = py
+                .eval("array[:,1::3]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+
+            let key2 = BorrowKey::from_array(view2.as_array_ptr());
+
+            assert_eq!(view2.strides(), &[80, 24]);
+
+            let view3 = py
+                .eval("array[:,::2]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+
+            let key3 = BorrowKey::from_array(view3.as_array_ptr());
+
+            assert_eq!(view3.strides(), &[80, 16]);
+
+            let view4 = py
+                .eval("array[:,1::2]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray2<f64>>()
+                .unwrap();
+
+            let key4 = BorrowKey::from_array(view4.as_array_ptr());
+
+            assert_eq!(view4.strides(), &[80, 16]);
+
+            assert!(!key3.conflicts(&key4));
+            assert!(key1.conflicts(&key3));
+            assert!(key2.conflicts(&key4));
+
+            // All aliasing indices like (0,7) and (2,0) are out of bounds.
+            assert!(!key1.conflicts(&key2));
+        });
+    }
+
+    #[test]
+    fn conflicts_agree_with_brute_force() {
+        fn next(state: &mut u64, bound: u64) -> u64 {
+            *state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (*state >> 33) % bound
+        }
+
+        fn offsets(layout: &[(usize, isize)]) -> Vec<isize> {
+            layout.iter().fold(vec![0], |offsets, &(dim, stride)| {
+                offsets
+                    .iter()
+                    .flat_map(|offset| (0..dim).map(move |idx| offset + idx as isize * stride))
+                    .collect()
+            })
+        }
+
+        let mut buf = vec![0_u8; 4096];
+        let base = unsafe { buf.as_mut_ptr().add(2048) };
+
+        let key = |state: &mut u64| {
+            let offset = next(state, 64) as isize;
+            let itemsize = 1 + next(state, 8) as isize;
+            let ndim = 1 + next(state, 3) as usize;
+
+            let layout = (0..ndim)
+                .map(|_| (next(state, 5) as usize, next(state, 81) as isize - 40))
+                .collect::<Box<[_]>>();
+
+            let offsets = offsets(&layout);
+            let range = match (offsets.iter().min(), offsets.iter().max()) {
+                (Some(min), Some(max)) => (*min + offset, *max + offset + itemsize),
+                _ => (offset, offset),
+            };
+
+            let key = BorrowKey {
+                range: unsafe { (base.offset(range.0), base.offset(range.1)) },
+                data_ptr: unsafe { base.offset(offset) },
+                layout: KeyLayout::Heap(layout),
+                itemsize,
+            };
+
+            let bytes = offsets
+                .iter()
+                .flat_map(|start| (0..itemsize).map(move |byte| offset + start + byte))
+                .collect::<Vec<_>>();
+
+            (key, bytes)
+        };
+
+        let mut state = 42;
+
+        for _ in 0..10_000 {
+            let (key1, bytes1) = key(&mut state);
+            let (key2, bytes2) = key(&mut state);
+
+            let overlaps = bytes1.iter().any(|byte| bytes2.contains(byte));
+
+            assert_eq!(key1.conflicts(&key2), overlaps);
+            assert_eq!(key2.conflicts(&key1), overlaps);
+        }
+    }
+
+    #[test]
+    fn borrow_multiple_arrays() {
+        Python::with_gil(|py| {
+            let array1 = PyArray::<f64, _>::zeros(py, 10, false);
+            let array2 = PyArray::<f64, _>::zeros(py, 10, false);
+
+            let base1 = base_address(py, array1.as_array_ptr());
+            let base2 = base_address(py, array2.as_array_ptr());
+
+            let key1 = BorrowKey::from_array(array1.as_array_ptr());
+            let _exclusive1 = array1.readwrite();
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base1];
+                assert_eq!(same_base_arrays.len(), 1);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+            }
+
+            let key2 = BorrowKey::from_array(array2.as_array_ptr());
+            let _shared2 = array2.readonly();
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 2);
+
+                let same_base_arrays = &borrow_flags[&base1];
+                assert_eq!(same_base_arrays.len(), 1);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+
+                let same_base_arrays = &borrow_flags[&base2];
+                assert_eq!(same_base_arrays.len(), 1);
+
+                let flag = same_base_arrays[&key2];
+                assert_eq!(flag, 1);
+            }
+        });
+    }
+
+    #[test]
+    fn borrow_multiple_views() {
+        Python::with_gil(|py| {
+            let array = PyArray::<f64, _>::zeros(py, 10, false);
+            let base = base_address(py, array.as_array_ptr());
+
+            let locals = [("array", array)].into_py_dict(py);
+
+            let view1 = py
+                .eval("array[:5]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+
+            let key1 = BorrowKey::from_array(view1.as_array_ptr());
+            let exclusive1 = view1.readwrite();
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 1);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+            }
+
+            let view2 = py
+                .eval("array[5:]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+
+            let key2 = BorrowKey::from_array(view2.as_array_ptr());
+            let shared2 = view2.readonly();
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 2);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+
+                let flag = same_base_arrays[&key2];
+                assert_eq!(flag, 1);
+            }
+
+            let view3 = py
+                .eval("array[5:]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+
+            let key3 = BorrowKey::from_array(view3.as_array_ptr());
+            let shared3 = view3.readonly();
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 2);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+
+                let flag = same_base_arrays[&key2];
+                assert_eq!(flag, 2);
+
+                let flag = same_base_arrays[&key3];
+                assert_eq!(flag, 2);
+            }
+
+            let view4 = py
+                .eval("array[7:]", None, Some(locals))
+                .unwrap()
+                .downcast::<PyArray1<f64>>()
+                .unwrap();
+
+            let key4 = BorrowKey::from_array(view4.as_array_ptr());
+            let shared4 = view4.readonly();
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 3);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+
+                let flag = same_base_arrays[&key2];
+                assert_eq!(flag, 2);
+
+                let flag = same_base_arrays[&key3];
+                assert_eq!(flag, 2);
+
+                let flag = same_base_arrays[&key4];
+                assert_eq!(flag, 1);
+            }
+
+            drop(shared2);
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 3);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+
+                let flag = same_base_arrays[&key2];
+                assert_eq!(flag, 1);
+
+                let flag = same_base_arrays[&key3];
+                assert_eq!(flag, 1);
+
+                let flag = same_base_arrays[&key4];
+                assert_eq!(flag, 1);
+            }
+
+            drop(shared3);
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 2);
+
+                let flag = same_base_arrays[&key1];
+                assert_eq!(flag, -1);
+
+                assert!(!same_base_arrays.contains_key(&key2));
+
+                assert!(!same_base_arrays.contains_key(&key3));
+
+                let flag = same_base_arrays[&key4];
+                assert_eq!(flag, 1);
+            }
+
+            drop(exclusive1);
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 1);
+
+                let same_base_arrays = &borrow_flags[&base];
+                assert_eq!(same_base_arrays.len(), 1);
+
+                assert!(!same_base_arrays.contains_key(&key1));
+
+                assert!(!same_base_arrays.contains_key(&key2));
+
+                assert!(!same_base_arrays.contains_key(&key3));
+
+                let flag = same_base_arrays[&key4];
+                assert_eq!(flag, 1);
+            }
+
+            drop(shared4);
+
+            {
+                let borrow_flags = get_borrow_flags(py);
+                assert_eq!(borrow_flags.len(), 0);
+            }
+        });
+    }
+
+    #[test]
+    fn same_base_arrays_keep_remaining_borrows() {
+        let mut buf = [0_u8; 8];
+        let base = buf.as_mut_ptr();
+
+        let key = |offset: usize| {
+            let data_ptr = unsafe { base.add(offset) };
+
+            BorrowKey {
+                range: (data_ptr, unsafe { data_ptr.add(1) }),
+                data_ptr,
+                layout: KeyLayout::new(&[1], &[1]),
+                itemsize: 1,
+            }
+        };
+
+        let mut same_base_arrays = SameBaseArrays::new(key(0), 1);
+        same_base_arrays.push(key(1), -1);
+        same_base_arrays.push(key(2), 2);
+        assert_eq!(same_base_arrays.len(), 3);
+
+        assert!(!same_base_arrays.remove(&key(0)).1);
+        assert_eq!(same_base_arrays.len(), 2);
+        assert!(!same_base_arrays.contains_key(&key(0)));
+        assert_eq!(same_base_arrays[&key(1)], -1);
+        assert_eq!(same_base_arrays[&key(2)], 2);
+
+        *same_base_arrays.get_mut(&key(2)).unwrap() += 1;
+        assert!(!same_base_arrays.remove(&key(1)).1);
+        assert_eq!(same_base_arrays[&key(2)], 3);
+
+        assert!(same_base_arrays.remove(&key(2)).1);
+    }
+
+    #[test]
+    fn panics_do_not_unwind_across_the_c_abi() {
+        let mut buf = [0_u8; 8];
+        let base = buf.as_mut_ptr();
+
+        let key = BorrowKey {
+            range: (base, unsafe { base.add(1) }),
+            data_ptr: base,
+            layout: KeyLayout::new(&[1], &[1]),
+            itemsize: 1,
+        };
+
+        let mut flags = BorrowFlags::default();
+
+        let rc = catch(|| {
+            let _ = flags.release(base, &key);
+            0
+        });
+        assert_eq!(rc, INTERNAL_ERROR);
+
+        let rc = catch(|| {
+            flags.acquire(base, key.clone(), None).unwrap();
+            assert!(flags.release(base, &key).is_empty());
+            0
+        });
+        assert_eq!(rc, 0);
+    }
+}
@@ -0,0 +1,45 @@
+//! Opt-in strict mode locking borrowed arrays against modification by Python
+//!
+//! See [`set_strict`].
+
+use pyo3::Python;
+
+use super::shared;
+
+/// Enables or disables strict mode.
+///
+/// While strict mode is enabled, acquiring a shared borrow clears the `WRITEABLE` flag of the borrowed array
+/// so that Python code cannot write into it, e.g. via `array[0] = 1.0`. The original flag is restored
+/// when the last shared borrow of that array is released, even if strict mode was disabled in the meantime.
+/// Furthermore, exclusive borrows of arrays which are not writeable or whose base arrays are not writeable
+/// are refused with [`BorrowError::NotWriteable`][crate::BorrowError::NotWriteable].
+///
+/// Note that only the borrowed array object itself is locked: its base array and other views into
+/// the same data remain writeable and Python code can still set the flag again explicitly.
+/// Strict mode applies to the borrows of all extensions using this crate and is disabled by default.
+///
+/// # Example
+///
+/// ```
+/// use numpy::{borrow::set_strict, PyArray1};
+/// use pyo3::{py_run, Python};
+///
+/// Python::with_gil(|py| {
+///     set_strict(py, true);
+///
+///     let array = PyArray1::<f64>::zeros(py, 10, false);
+///
+///     {
+///         let _shared = array.readonly();
+///
+///         py_run!(py, array, "assert not array.flags.writeable");
+///     }
+///
+///     py_run!(py, array, "assert array.flags.writeable");
+///
+///     set_strict(py, false);
+/// });
+/// ```
+pub fn set_strict(py: Python, enabled: bool) {
+    shared::set_strict(py, enabled);
+}
@@ -1,18 +1,23 @@
+use std::cell::UnsafeCell;
+use std::char::{self, REPLACEMENT_CHARACTER};
+use std::collections::hash_map::Entry;
+use std::fmt::{self, Write};
 use std::mem::size_of;
 use std::os::raw::{
     c_char, c_int, c_long, c_longlong, c_short, c_uint, c_ulong, c_ulonglong, c_ushort,
 };
 use std::ptr;
 
+use ahash::AHashMap;
 #[cfg(feature = "half")]
 use half::f16;
 use num_traits::{Bounded, Zero};
 use pyo3::{
     exceptions::{PyIndexError, PyValueError},
-    ffi::{self, PyTuple_Size},
+    ffi::{self, PyTuple_Size, Py_UCS4},
     pyobject_native_type_extract, pyobject_native_type_named,
-    types::{PyDict, PyTuple, PyType},
-    AsPyPointer, FromPyObject, FromPyPointer, IntoPyPointer, PyAny, PyNativeType, PyObject,
+    types::{PyDict, PyList, PyTuple, PyType},
+    AsPyPointer, FromPyObject, FromPyPointer, IntoPyPointer, Py, PyAny, PyNativeType, PyObject,
     PyResult, PyTypeInfo, Python, ToPyObject,
 };
 
@@ -93,6 +98,73 @@ impl PyArrayDescr {
         inner(py, ob.to_object(py))
     }
 
+    /// Creates a new structured type descriptor ("dtype") from a list of fields.
+    ///
+    /// Each field is given by its name, its type descriptor and its offset in bytes.
+    /// `itemsize` is the total size of a record including any trailing padding.
+    ///
+    /// If `align` is true, NumPy will check that the offsets respect the alignment
+    /// of the field types and the resulting type descriptor will be an
+    /// [aligned struct][Self::is_aligned_struct].
+    ///
+    /// Equivalent to invoking the constructor of [`numpy.dtype`][dtype]
+    /// using a dictionary with the keys `names`, `formats`, `offsets` and `itemsize`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{dtype, PyArrayDescr};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let dt = PyArrayDescr::from_fields(
+    ///         py,
+    ///         &[("x", dtype::<f64>(py), 0), ("id", dtype::<u32>(py), 8)],
+    ///         16,
+    ///         true,
+    ///     )
+    ///     .unwrap();
+    ///
+    ///     assert_eq!(dt.names(), Some(vec!["x", "id"]));
+    ///     assert_eq!(dt.itemsize(), 16);
+    ///     assert!(dt.is_aligned_struct());
+    /// });
+    /// ```
+    ///
+    /// [dtype]: https://numpy.org/doc/stable/reference/generated/numpy.dtype.html
+    pub fn from_fields<'py>(
+        py: Python<'py>,
+        fields: &[(&str, &PyArrayDescr, usize)],
+        itemsize: usize,
+        align: bool,
+    ) -> PyResult<&'py Self> {
+        let names = PyList::empty(py);
+        let formats = PyList::empty(py);
+        let offsets = PyList::empty(py);
+
+        for (name, dtype, offset) in fields {
+            names.append(name)?;
+            formats.append(dtype)?;
+            offsets.append(offset)?;
+        }
+
+        let spec = PyDict::new(py);
+        spec.set_item("names", names)?;
+        spec.set_item("formats", formats)?;
+        spec.set_item("offsets", offsets)?;
+        spec.set_item("itemsize", itemsize)?;
+
+        let mut descr: *mut PyArray_Descr = ptr::null_mut();
+        unsafe {
+            if align {
+                PY_ARRAY_API.PyArray_DescrAlignConverter(py, spec.as_ptr(), &mut descr as *mut _);
+            } else {
+                PY_ARRAY_API.PyArray_DescrConverter(py, spec.as_ptr(), &mut descr as *mut _);
+            }
+            py.from_owned_ptr_or_err(descr as _)
+        }
+    }
+
     /// Returns `self` as `*mut PyArray_Descr`.
     pub fn as_dtype_ptr(&self) -> *mut PyArray_Descr {
         self.as_ptr() as _
@@ -116,14 +188,50 @@ impl PyArrayDescr {
     }
 
     /// Returns true if two type descriptors are equivalent.
+    ///
+    /// For structured types, this additionally requires that both types have the same
+    /// fields in the same order, i.e. names, offsets and field types match one-by-one.
     pub fn is_equiv_to(&self, other: &Self) -> bool {
         let self_ptr = self.as_dtype_ptr();
         let other_ptr = other.as_dtype_ptr();
 
-        unsafe {
-            self_ptr == other_ptr
-                || PY_ARRAY_API.PyArray_EquivTypes(self.py(), self_ptr, other_ptr) != 0
+        if self_ptr == other_ptr {
+            return true;
+        }
+
+        if unsafe { PY_ARRAY_API.PyArray_EquivTypes(self.py(), self_ptr, other_ptr) } == 0 {
+            return false;
         }
+
+        if self.has_fields() || other.has_fields() {
+            return self.has_equiv_fields(other);
+        }
+
+        true
+    }
+
+    fn has_equiv_fields(&self, other: &Self) -> bool {
+        if self.itemsize() != other.itemsize() {
+            return false;
+        }
+
+        let (names, other_names) = match (self.names(), other.names()) {
+            (Some(names), Some(other_names)) => (names, other_names),
+            _ => return false,
+        };
+
+        if names != other_names {
+            return false;
+        }
+
+        names
+            .iter()
+            .all(|name| match (self.get_field(name), other.get_field(name)) {
+                (Ok((dtype, offset)), Ok((other_dtype, other_offset))) => {
+                    offset == other_offset && dtype.is_equiv_to(other_dtype)
+                }
+                _ => false,
+            })
     }
 
     fn from_npy_type(py: Python, npy_type: NPY_TYPES) -> &Self {
@@ -363,8 +471,9 @@ impl PyArrayDescr {
 
 /// Represents that a type can be an element of `PyArray`.
 ///
-/// Currently, only integer/float/complex/object types are supported. The [NumPy documentation][enumerated-types]
-/// list the other built-in types which we are not yet implemented.
+/// Currently, only integer/float/complex/object types, fixed-width byte and Unicode strings
+/// as well as fixed-size arrays of these types are supported.
+/// The [NumPy documentation][enumerated-types] list the other built-in types which we are not yet implemented.
 ///
 /// # Safety
 ///
@@ -387,7 +496,27 @@ impl PyArrayDescr {
 /// You can however create [`Array<Py<T>, D>`][ndarray::Array] and turn that into a NumPy array
 /// safely and efficiently using [`from_owned_object_array`][crate::PyArray::from_owned_object_array].
 ///
+/// # Record types
+///
+/// `#[repr(C)]` structs whose fields all implement `Element` can be mapped to
+/// [structured data types][structured-arrays] by enabling the `derive` feature
+/// and using `#[derive(Element)]`. The generated type descriptor uses the field names,
+/// offsets and alignment chosen by the compiler, cf. [`PyArrayDescr::from_fields`].
+///
+/// # Sub-array types
+///
+/// Fixed-size arrays `[T; N]` map to [sub-array data types][subarray-types] like `dtype(('<f4', (3,)))`
+/// which can be used as the types of the fields of structured data types.
+///
+/// NumPy itself never creates arrays of sub-array types but absorbs them into additional trailing axes,
+/// e.g. an array of `dtype(('<f4', (3,)))` with shape `(4,)` becomes an array of `float32` with shape `(4, 3)`.
+/// Hence, creating a `PyArray<[T; N], D>` panics and extracting one fails. Instead, the trailing axis
+/// of a `PyArray<T, D>` can be viewed as elements of type `[T; N]` using [`PyReadonlyArray::as_array_of`].
+///
 /// [enumerated-types]: https://numpy.org/doc/stable/reference/c-api/dtype.html#enumerated-types
+/// [subarray-types]: https://numpy.org/doc/stable/reference/arrays.dtypes.html#arrays-dtypes-constructing
+/// [`PyReadonlyArray::as_array_of`]: crate::PyReadonlyArray::as_array_of
+/// [structured-arrays]: https://numpy.org/doc/stable/user/basics.rec.html
 pub unsafe trait Element: Clone + Send {
     /// Flag that indicates whether this type is trivially copyable.
     ///
@@ -493,6 +622,258 @@ unsafe impl Element for PyObject {
     }
 }
 
+/// A fixed-width byte string which maps to NumPy's [`bytes_`][scalars-bytes] type, e.g. `dtype('S16')`.
+///
+/// NumPy pads shorter strings with trailing NUL bytes which are removed by [`as_bytes`][Self::as_bytes].
+///
+/// # Example
+///
+/// ```
+/// use numpy::{PyArray1, PyFixedString};
+/// use pyo3::Python;
+///
+/// Python::with_gil(|py| {
+///     let array = py
+///         .eval("__import__('numpy').array([b'foo', b'bar'], dtype='S8')", None, None)
+///         .unwrap()
+///         .downcast::<PyArray1<PyFixedString<8>>>()
+///         .unwrap();
+///
+///     assert_eq!(array.get_owned(1).unwrap().as_bytes(), b"bar");
+/// });
+/// ```
+///
+/// Using `PyFixedString<0>` as an element type fails to compile as NumPy would treat it as a string of unspecified length.
+///
+/// [scalars-bytes]: https://numpy.org/doc/stable/reference/arrays.scalars.html#numpy.bytes_
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct PyFixedString<const N: usize>([u8; N]);
+
+impl<const N: usize> PyFixedString<N> {
+    /// Returns the contents of the string without any trailing NUL bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        let len = self
+            .0
+            .iter()
+            .rposition(|&byte| byte != 0)
+            .map_or(0, |pos| pos + 1);
+        &self.0[..len]
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for PyFixedString<N> {
+    fn from(val: [u8; N]) -> Self {
+        Self(val)
+    }
+}
+
+impl<const N: usize> From<PyFixedString<N>> for [u8; N] {
+    fn from(val: PyFixedString<N>) -> Self {
+        val.0
+    }
+}
+
+impl<const N: usize> fmt::Display for PyFixedString<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&String::from_utf8_lossy(self.as_bytes()))
+    }
+}
+
+unsafe impl<const N: usize> Element for PyFixedString<N> {
+    const IS_COPY: bool = true;
+
+    fn get_dtype(py: Python) -> &PyArrayDescr {
+        let () = NonEmpty::<N>::CHECK;
+
+        static DTYPES: TypeDescriptors = TypeDescriptors::new(NPY_TYPES::NPY_STRING);
+
+        DTYPES.from_size(py, N)
+    }
+}
+
+/// A fixed-width Unicode string which maps to NumPy's [`str_`][scalars-str] type, e.g. `dtype('<U32')`.
+///
+/// NumPy stores these strings as UCS4 code points padded with trailing NUL characters
+/// which are removed by [`as_ucs4`][Self::as_ucs4]. The [`Display`][fmt::Display] implementation
+/// decodes the code points, replacing invalid ones by [`U+FFFD`][std::char::REPLACEMENT_CHARACTER].
+///
+/// # Example
+///
+/// ```
+/// use numpy::{PyArray1, PyFixedUnicode};
+/// use pyo3::Python;
+///
+/// Python::with_gil(|py| {
+///     let array = py
+///         .eval("__import__('numpy').array(['foo', 'bär'], dtype='U4')", None, None)
+///         .unwrap()
+///         .downcast::<PyArray1<PyFixedUnicode<4>>>()
+///         .unwrap();
+///
+///     assert_eq!(array.get_owned(1).unwrap().to_string(), "bär");
+/// });
+/// ```
+///
+/// Using `PyFixedUnicode<0>` as an element type fails to compile as NumPy would treat it as a string of unspecified length.
+///
+/// [scalars-str]: https://numpy.org/doc/stable/reference/arrays.scalars.html#numpy.str_
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct PyFixedUnicode<const N: usize>([Py_UCS4; N]);
+
+impl<const N: usize> PyFixedUnicode<N> {
+    /// Returns the code points of the string without any trailing NUL characters.
+    pub fn as_ucs4(&self) -> &[Py_UCS4] {
+        let len = self
+            .0
+            .iter()
+            .rposition(|&cp| cp != 0)
+            .map_or(0, |pos| pos + 1);
+        &self.0[..len]
+    }
+}
+
+impl<const N: usize> From<[Py_UCS4; N]> for PyFixedUnicode<N> {
+    fn from(val: [Py_UCS4; N]) -> Self {
+        Self(val)
+    }
+}
+
+impl<const N: usize> From<PyFixedUnicode<N>> for [Py_UCS4; N] {
+    fn from(val: PyFixedUnicode<N>) -> Self {
+        val.0
+    }
+}
+
+impl<const N: usize> fmt::Display for PyFixedUnicode<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &cp in self.as_ucs4() {
+            f.write_char(char::from_u32(cp).unwrap_or(REPLACEMENT_CHARACTER))?;
+        }
+
+        Ok(())
+    }
+}
+
+unsafe impl<const N: usize> Element for PyFixedUnicode<N> {
+    const IS_COPY: bool = true;
+
+    fn get_dtype(py: Python) -> &PyArrayDescr {
+        let () = NonEmpty::<N>::CHECK;
+
+        static DTYPES: TypeDescriptors = TypeDescriptors::new(NPY_TYPES::NPY_UNICODE);
+
+        DTYPES.from_size(py, size_of::<Self>())
+    }
+}
+
+/// Fails to evaluate `CHECK` if `N` is zero as NumPy would treat a data type of size zero as unsized.
+struct NonEmpty<const N: usize>;
+
+impl<const N: usize> NonEmpty<N> {
+    const CHECK: () = [()][(N == 0) as usize];
+}
+
+unsafe impl<T: Element, const N: usize> Element for [T; N] {
+    const IS_COPY: bool = T::IS_COPY;
+
+    fn get_dtype(py: Python) -> &PyArrayDescr {
+        static DTYPES: SubarrayDescriptors = SubarrayDescriptors::new();
+
+        DTYPES.from_base(py, T::get_dtype(py), N)
+    }
+}
+
+/// Caches the type descriptors of flexible types like `NPY_STRING` indexed by their element size.
+struct TypeDescriptors {
+    npy_type: NPY_TYPES,
+    dtypes: UnsafeCell<Option<AHashMap<usize, Py<PyArrayDescr>>>>,
+}
+
+unsafe impl Sync for TypeDescriptors {}
+
+impl TypeDescriptors {
+    const fn new(npy_type: NPY_TYPES) -> Self {
+        Self {
+            npy_type,
+            dtypes: UnsafeCell::new(None),
+        }
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn get(&self) -> &mut AHashMap<usize, Py<PyArrayDescr>> {
+        (*self.dtypes.get()).get_or_insert_with(AHashMap::new)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn from_size<'py>(&'py self, py: Python<'py>, size: usize) -> &'py PyArrayDescr {
+        // SAFETY: We hold the GIL and we do not call into user code which might re-enter.
+        let dtypes = unsafe { self.get() };
+
+        match dtypes.entry(size) {
+            Entry::Occupied(entry) => entry.into_mut().as_ref(py),
+            Entry::Vacant(entry) => {
+                let dtype = PyArrayDescr::new_from_npy_type(py, self.npy_type);
+
+                // SAFETY: `dtype` is a newly created and hence unshared type descriptor.
+                unsafe {
+                    (*dtype.as_dtype_ptr()).elsize = size as c_int;
+                }
+
+                entry.insert(dtype.into()).as_ref(py)
+            }
+        }
+    }
+}
+
+/// Caches the sub-array type descriptors of `[T; N]` indexed by the type descriptor of `T` and `N`.
+struct SubarrayDescriptors {
+    dtypes: UnsafeCell<Option<SubarrayDescriptorMap>>,
+}
+
+type SubarrayDescriptorMap = AHashMap<(usize, usize), Py<PyArrayDescr>>;
+
+unsafe impl Sync for SubarrayDescriptors {}
+
+impl SubarrayDescriptors {
+    const fn new() -> Self {
+        Self {
+            dtypes: UnsafeCell::new(None),
+        }
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn get(&self) -> &mut SubarrayDescriptorMap {
+        (*self.dtypes.get()).get_or_insert_with(AHashMap::new)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn from_base<'py>(
+        &'py self,
+        py: Python<'py>,
+        base: &'py PyArrayDescr,
+        len: usize,
+    ) -> &'py PyArrayDescr {
+        // The cached sub-array type descriptor keeps `base` alive,
+        // hence its address cannot be reused by another type descriptor.
+        let key = (base.as_dtype_ptr() as usize, len);
+
+        // SAFETY: We hold the GIL and we do not call into user code which might re-enter.
+        let dtypes = unsafe { self.get() };
+
+        match dtypes.entry(key) {
+            Entry::Occupied(entry) => entry.into_mut().as_ref(py),
+            Entry::Vacant(entry) => {
+                let dtype = PyArrayDescr::new(py, &(base, (len,)))
+                    .expect("Failed to create sub-array type descriptor");
+
+                entry.insert(dtype.into()).as_ref(py)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -554,6 +935,65 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_dtype_fixed_width_strings() {
+        Python::with_gil(|py| {
+            let dt = dtype::<PyFixedString<16>>(py);
+            assert_eq!(dt.num(), NPY_TYPES::NPY_STRING as c_int);
+            assert_eq!(dt.kind(), b'S');
+            assert_eq!(dt.itemsize(), 16);
+            assert!(dt.is_equiv_to(PyArrayDescr::new(py, "S16").unwrap()));
+            assert!(!dt.is_equiv_to(PyArrayDescr::new(py, "S8").unwrap()));
+            assert!(dt.is(dtype::<PyFixedString<16>>(py)));
+
+            let dt = dtype::<PyFixedUnicode<32>>(py);
+            assert_eq!(dt.num(), NPY_TYPES::NPY_UNICODE as c_int);
+            assert_eq!(dt.kind(), b'U');
+            assert_eq!(dt.itemsize(), 4 * 32);
+            assert!(dt.is_equiv_to(PyArrayDescr::new(py, "U32").unwrap()));
+            assert!(!dt.is_equiv_to(PyArrayDescr::new(py, "U16").unwrap()));
+        });
+    }
+
+    #[test]
+    fn test_fixed_width_string_conversions() {
+        let string = PyFixedString::from(*b"foo\0\0");
+        assert_eq!(string.as_bytes(), b"foo");
+        assert_eq!(string.to_string(), "foo");
+
+        let string = PyFixedString::from([0_u8; 4]);
+        assert_eq!(string.as_bytes(), b"");
+
+        let string = PyFixedUnicode::from([0x62, 0xe4, 0x72, 0, 0]);
+        assert_eq!(string.as_ucs4(), &[0x62, 0xe4, 0x72]);
+        assert_eq!(string.to_string(), "bär");
+
+        let string = PyFixedUnicode::from([0x61, 0xd800]);
+        assert_eq!(string.to_string(), "a\u{fffd}");
+    }
+
+    #[test]
+    fn test_dtype_subarray() {
+        Python::with_gil(|py| {
+            let dt = dtype::<[f32; 3]>(py);
+            assert!(dt.has_subarray());
+            assert_eq!(dt.shape(), vec![3]);
+            assert_eq!(dt.itemsize(), 12);
+            assert!(dt.base().is_equiv_to(dtype::<f32>(py)));
+            assert!(dt.is_equiv_to(PyArrayDescr::new(py, "(3,)f4").unwrap()));
+            assert!(dt.is(dtype::<[f32; 3]>(py)));
+            assert!(!dt.is(dtype::<[f32; 4]>(py)));
+            assert!(!dt.is(dtype::<[f64; 3]>(py)));
+
+            let dt = dtype::<[[u8; 2]; 4]>(py);
+            assert_eq!(dt.shape(), vec![4, 2]);
+            assert!(dt.base().is_equiv_to(dtype::<u8>(py)));
+
+            assert!(<[f64; 2]>::IS_COPY);
+            assert!(!<[PyObject; 2]>::IS_COPY);
+        });
+    }
+
     #[test]
     fn test_dtype_methods_scalar() {
         Python::with_gil(|py| {
@@ -5,6 +5,8 @@ use std::fmt;
 
 use pyo3::{exceptions::PyTypeError, Py, PyErr, PyErrArguments, PyObject, Python, ToPyObject};
 
+use crate::array::Casting;
+use crate::borrow::BorrowInfo;
 use crate::dtype::PyArrayDescr;
 
 /// Array dimensionality should be limited by [`NPY_MAXDIMS`][NPY_MAXDIMS] which is currently 32.´
@@ -105,6 +107,62 @@ impl From<TypeError<'_>> for PyErr {
     }
 }
 
+/// Represents that the element type of an array cannot be cast according to the given casting rule.
+#[derive(Debug)]
+pub struct CastError<'a> {
+    from: &'a PyArrayDescr,
+    to: &'a PyArrayDescr,
+    casting: Casting,
+}
+
+impl<'a> CastError<'a> {
+    pub(crate) fn new(from: &'a PyArrayDescr, to: &'a PyArrayDescr, casting: Casting) -> Self {
+        Self { from, to, casting }
+    }
+}
+
+impl fmt::Display for CastError<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "cannot cast array data from {} to {} according to the rule '{}'",
+            self.from, self.to, self.casting
+        )
+    }
+}
+
+impl Error for CastError<'_> {}
+
+struct CastErrorArguments {
+    from: Py<PyArrayDescr>,
+    to: Py<PyArrayDescr>,
+    casting: Casting,
+}
+
+impl PyErrArguments for CastErrorArguments {
+    fn arguments(self, py: Python) -> PyObject {
+        let err = CastError {
+            from: self.from.as_ref(py),
+            to: self.to.as_ref(py),
+            casting: self.casting,
+        };
+
+        err.to_string().to_object(py)
+    }
+}
+
+impl From<CastError<'_>> for PyErr {
+    fn from(err: CastError<'_>) -> PyErr {
+        let args = CastErrorArguments {
+            from: err.from.into(),
+            to: err.to.into(),
+            casting: err.casting,
+        };
+
+        PyTypeError::new_err(args)
+    }
+}
+
 /// Represents that given `Vec` cannot be treated as an array.
 #[derive(Debug)]
 pub struct FromVecError {
@@ -148,15 +206,26 @@ impl_pyerr!(NotContiguousError);
 pub enum BorrowError {
     /// The given array is already borrowed
     AlreadyBorrowed,
+    /// The given array is already borrowed by the described borrow
+    ///
+    /// This is returned instead of [`AlreadyBorrowed`][Self::AlreadyBorrowed]
+    /// if [diagnostics are enabled][crate::borrow::set_diagnostics].
+    AlreadyBorrowedBy(Box<BorrowInfo>),
     /// The given array is not writeable
     NotWriteable,
+    /// The given array is not [frozen][crate::PyArray::freeze]
+    NotFrozen,
 }
 
 impl fmt::Display for BorrowError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::AlreadyBorrowed => write!(f, "The given array is already borrowed"),
+            Self::AlreadyBorrowedBy(info) => {
+                write!(f, "The given array is already borrowed by {}", info)
+            }
             Self::NotWriteable => write!(f, "The given array is not writeable"),
+            Self::NotFrozen => write!(f, "The given array is not frozen"),
         }
     }
 }
@@ -0,0 +1,274 @@
+//! Control and report floating-point errors
+//!
+//! NumPy checks the floating-point exception flags after its operations and depending on the [error state][errstate]
+//! ignores the errors, issues a `RuntimeWarning`, raises a `FloatingPointError` or calls a function registered using
+//! [`seterrcall`][seterrcall]. This module provides the scoped [`ErrState`] guard to change that state temporarily
+//! and the [`check_fperr`] function so that Rust code can report floating-point errors in the same manner.
+//!
+//! # Example
+//!
+//! ```
+//! use numpy::{
+//!     errstate::{check_fperr, clear_fperr, ErrState, FloatErrorMode},
+//!     PyArray1,
+//! };
+//! use pyo3::{exceptions::PyFloatingPointError, Python};
+//!
+//! Python::with_gil(|py| {
+//!     let array = PyArray1::from_slice(py, &[1.0, 0.0]);
+//!
+//!     let _guard = ErrState::new()
+//!         .divide(FloatErrorMode::Raise)
+//!         .enter(py)
+//!         .unwrap();
+//!
+//!     clear_fperr(py);
+//!
+//!     let inverse = array.readonly().as_array().mapv(|x| 1.0 / x);
+//!     assert_eq!(inverse[1], f64::INFINITY);
+//!
+//!     let err = check_fperr(py, "inverse").unwrap_err();
+//!     assert!(err.is_instance_of::<PyFloatingPointError>(py));
+//! });
+//! ```
+//!
+//! [errstate]: https://numpy.org/doc/stable/reference/routines.err.html
+//! [seterrcall]: https://numpy.org/doc/stable/reference/generated/numpy.seterrcall.html
+
+use std::ffi::CString;
+use std::os::raw::c_int;
+use std::ptr;
+
+use pyo3::{
+    types::{PyDict, PyModule},
+    AsPyPointer, PyErr, PyObject, PyResult, Python,
+};
+
+use crate::npyffi::PY_UFUNC_API;
+
+/// How NumPy handles a kind of floating-point error, cf. [`numpy.seterr`][seterr].
+///
+/// [seterr]: https://numpy.org/doc/stable/reference/generated/numpy.seterr.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FloatErrorMode {
+    /// Take no action when the error occurs.
+    Ignore,
+    /// Issue a `RuntimeWarning`.
+    Warn,
+    /// Raise a `FloatingPointError`.
+    Raise,
+    /// Call the function registered using `numpy.seterrcall`.
+    Call,
+}
+
+impl FloatErrorMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Ignore => "ignore",
+            Self::Warn => "warn",
+            Self::Raise => "raise",
+            Self::Call => "call",
+        }
+    }
+}
+
+/// Changes NumPy's handling of floating-point errors until the returned guard is dropped.
+///
+/// Kinds of errors for which no mode is set keep their current handling.
+///
+/// See also [`numpy.errstate`][errstate].
+///
+/// [errstate]: https://numpy.org/doc/stable/reference/generated/numpy.errstate.html
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ErrState {
+    divide: Option<FloatErrorMode>,
+    over: Option<FloatErrorMode>,
+    under: Option<FloatErrorMode>,
+    invalid: Option<FloatErrorMode>,
+}
+
+impl ErrState {
+    /// Creates an error state which does not change the handling of any kind of error.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an error state which handles all kinds of errors using the given `mode`.
+    pub fn all(mode: FloatErrorMode) -> Self {
+        Self {
+            divide: Some(mode),
+            over: Some(mode),
+            under: Some(mode),
+            invalid: Some(mode),
+        }
+    }
+
+    /// Sets the handling of division by zero.
+    pub fn divide(mut self, mode: FloatErrorMode) -> Self {
+        self.divide = Some(mode);
+        self
+    }
+
+    /// Sets the handling of floating-point overflow.
+    pub fn over(mut self, mode: FloatErrorMode) -> Self {
+        self.over = Some(mode);
+        self
+    }
+
+    /// Sets the handling of floating-point underflow.
+    pub fn under(mut self, mode: FloatErrorMode) -> Self {
+        self.under = Some(mode);
+        self
+    }
+
+    /// Sets the handling of invalid floating-point operations.
+    pub fn invalid(mut self, mode: FloatErrorMode) -> Self {
+        self.invalid = Some(mode);
+        self
+    }
+
+    /// Applies the error state, returning a guard which restores the previous state when dropped.
+    pub fn enter<'py>(self, py: Python<'py>) -> PyResult<ErrStateGuard<'py>> {
+        let kwargs = PyDict::new(py);
+
+        for (name, mode) in [
+            ("divide", self.divide),
+            ("over", self.over),
+            ("under", self.under),
+            ("invalid", self.invalid),
+        ]
+        .iter()
+        {
+            if let Some(mode) = mode {
+                kwargs.set_item(name, mode.as_str())?;
+            }
+        }
+
+        let old = PyModule::import(py, "numpy")?
+            .call_method("seterr", (), Some(kwargs))?
+            .downcast::<PyDict>()?;
+
+        Ok(ErrStateGuard { old })
+    }
+}
+
+/// Restores the previous floating-point error state when dropped, cf. [`ErrState::enter`].
+#[derive(Debug)]
+pub struct ErrStateGuard<'py> {
+    old: &'py PyDict,
+}
+
+impl Drop for ErrStateGuard<'_> {
+    fn drop(&mut self) {
+        let py = self.old.py();
+
+        let res = PyModule::import(py, "numpy")
+            .and_then(|numpy| numpy.call_method("seterr", (), Some(self.old)));
+
+        if let Err(err) = res {
+            err.print(py);
+        }
+    }
+}
+
+/// The floating-point exception flags, cf. [`get_fperr`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FloatErrors(c_int);
+
+impl FloatErrors {
+    const DIVIDE: c_int = 1;
+    const OVER: c_int = 2;
+    const UNDER: c_int = 4;
+    const INVALID: c_int = 8;
+
+    /// Returns `true` if a division by zero occurred.
+    pub fn divide(self) -> bool {
+        self.0 & Self::DIVIDE != 0
+    }
+
+    /// Returns `true` if a floating-point overflow occurred.
+    pub fn over(self) -> bool {
+        self.0 & Self::OVER != 0
+    }
+
+    /// Returns `true` if a floating-point underflow occurred.
+    pub fn under(self) -> bool {
+        self.0 & Self::UNDER != 0
+    }
+
+    /// Returns `true` if an invalid floating-point operation occurred.
+    pub fn invalid(self) -> bool {
+        self.0 & Self::INVALID != 0
+    }
+
+    /// Returns `true` if no floating-point error occurred.
+    pub fn is_empty(self) -> bool {
+        self.0 & (Self::DIVIDE | Self::OVER | Self::UNDER | Self::INVALID) == 0
+    }
+}
+
+/// Clears the floating-point exception flags of the current thread.
+///
+/// This should be called before running code whose errors are reported using [`get_fperr`] or [`check_fperr`].
+///
+/// See also [`PyUFunc_clearfperr`][clearfperr].
+///
+/// [clearfperr]: https://numpy.org/doc/stable/reference/c-api/ufunc.html#c.PyUFunc_clearfperr
+pub fn clear_fperr(py: Python) {
+    unsafe {
+        PY_UFUNC_API.PyUFunc_clearfperr(py);
+    }
+}
+
+/// Returns and clears the floating-point exception flags of the current thread.
+///
+/// See also [`PyUFunc_getfperr`][getfperr].
+///
+/// [getfperr]: https://numpy.org/doc/stable/reference/c-api/ufunc.html#c.PyUFunc_getfperr
+pub fn get_fperr(py: Python) -> FloatErrors {
+    FloatErrors(unsafe { PY_UFUNC_API.PyUFunc_getfperr(py) })
+}
+
+/// Handles and clears the floating-point exception flags of the current thread according to NumPy's error state.
+///
+/// Depending on the error state, this will ignore the errors, issue a `RuntimeWarning`,
+/// return a `FloatingPointError` or call the registered function, passing `name` as the name of the failed operation.
+/// Errors are also returned if a warning is turned into an exception or the registered function raises.
+///
+/// See also [`PyUFunc_checkfperr`][checkfperr].
+///
+/// [checkfperr]: https://numpy.org/doc/stable/reference/c-api/ufunc.html#c.PyUFunc_checkfperr
+pub fn check_fperr(py: Python, name: &str) -> PyResult<()> {
+    let name = CString::new(name)?;
+
+    let mut bufsize = 0;
+    let mut errmask = 0;
+    let mut errobj = ptr::null_mut();
+
+    let res = unsafe {
+        PY_UFUNC_API.PyUFunc_GetPyValues(
+            py,
+            name.as_ptr() as *mut _,
+            &mut bufsize,
+            &mut errmask,
+            &mut errobj,
+        )
+    };
+
+    if res < 0 {
+        return Err(PyErr::fetch(py));
+    }
+
+    // `PyUFunc_GetPyValues` returns a new reference to the error object.
+    let errobj = unsafe { PyObject::from_owned_ptr(py, errobj) };
+
+    let mut first = 1;
+
+    let res = unsafe { PY_UFUNC_API.PyUFunc_checkfperr(py, errmask, errobj.as_ptr(), &mut first) };
+
+    if res < 0 {
+        return Err(PyErr::fetch(py));
+    }
+
+    Ok(())
+}
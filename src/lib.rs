@@ -37,38 +37,58 @@
 #![allow(clippy::needless_lifetimes)]
 
 pub mod array;
+mod array_like;
 pub mod borrow;
 pub mod convert;
 pub mod datetime;
 mod dtype;
 mod error;
+pub mod errstate;
 pub mod npyffi;
 pub mod npyiter;
+mod reductions;
 mod slice_container;
 mod sum_products;
+mod ufunc;
+mod untyped_array;
 
 pub use ndarray;
 pub use pyo3;
 
 pub use crate::array::{
-    get_array_module, PyArray, PyArray0, PyArray1, PyArray2, PyArray3, PyArray4, PyArray5,
-    PyArray6, PyArrayDyn,
+    get_array_module, Casting, PyArray, PyArray0, PyArray1, PyArray2, PyArray3, PyArray4, PyArray5,
+    PyArray6, PyArrayDyn, SearchSide, SortKind,
+};
+pub use crate::array_like::{
+    PyArrayLike, PyArrayLike0, PyArrayLike1, PyArrayLike2, PyArrayLike3, PyArrayLike4,
+    PyArrayLike5, PyArrayLike6, PyArrayLikeDyn, PyWritebackArray, PyWritebackArray0,
+    PyWritebackArray1, PyWritebackArray2, PyWritebackArray3, PyWritebackArray4, PyWritebackArray5,
+    PyWritebackArray6, PyWritebackArrayDyn,
 };
 pub use crate::borrow::{
-    PyReadonlyArray, PyReadonlyArray1, PyReadonlyArray2, PyReadonlyArray3, PyReadonlyArray4,
-    PyReadonlyArray5, PyReadonlyArray6, PyReadonlyArrayDyn, PyReadwriteArray, PyReadwriteArray1,
-    PyReadwriteArray2, PyReadwriteArray3, PyReadwriteArray4, PyReadwriteArray5, PyReadwriteArray6,
-    PyReadwriteArrayDyn,
+    DetachedReadonlyArray, DetachedReadwriteArray, PyFrozenArray, PyFrozenArray0, PyFrozenArray1,
+    PyFrozenArray2, PyFrozenArray3, PyFrozenArray4, PyFrozenArray5, PyFrozenArray6,
+    PyFrozenArrayDyn, PyReadonlyArray, PyReadonlyArray1, PyReadonlyArray2, PyReadonlyArray3,
+    PyReadonlyArray4, PyReadonlyArray5, PyReadonlyArray6, PyReadonlyArrayDyn, PyReadwriteArray,
+    PyReadwriteArray1, PyReadwriteArray2, PyReadwriteArray3, PyReadwriteArray4, PyReadwriteArray5,
+    PyReadwriteArray6, PyReadwriteArrayChunks, PyReadwriteArrayDyn, SharedReadonlyArray,
 };
 pub use crate::convert::{IntoPyArray, NpyIndex, ToNpyDims, ToPyArray};
-pub use crate::dtype::{dtype, Complex32, Complex64, Element, PyArrayDescr};
+pub use crate::dtype::{
+    dtype, Complex32, Complex64, Element, PyArrayDescr, PyFixedString, PyFixedUnicode,
+};
 pub use crate::error::{BorrowError, FromVecError, NotContiguousError};
 pub use crate::npyffi::{PY_ARRAY_API, PY_UFUNC_API};
 #[allow(deprecated)]
 pub use crate::npyiter::{
     IterMode, NpyIterFlag, NpyMultiIter, NpyMultiIterBuilder, NpySingleIter, NpySingleIterBuilder,
 };
+pub use crate::reductions::{Accumulate, Average};
 pub use crate::sum_products::{dot, einsum, inner};
+pub use crate::ufunc::{CoreFn, ElementwiseFn, PyGUFuncBuilder, PyUFunc, PyUFuncBuilder};
+pub use crate::untyped_array::PyUntypedArray;
+#[cfg(feature = "derive")]
+pub use numpy_derive::Element;
 
 pub use ndarray::{array, Ix1, Ix2, Ix3, Ix4, Ix5, Ix6, IxDyn};
 
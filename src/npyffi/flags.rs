@@ -81,3 +81,8 @@ pub const NPY_OBJECT_DTYPE_FLAGS: npy_char = NPY_LIST_PICKLE
     | NPY_ITEM_REFCOUNT
     | NPY_NEEDS_INIT
     | NPY_NEEDS_PYAPI;
+
+/// The maximum number of dimensions of an array.
+///
+/// Passed as the `axis` argument, it makes reductions like `PyArray_Sum` operate on the flattened array.
+pub const NPY_MAXDIMS: c_int = 32;
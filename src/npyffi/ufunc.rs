@@ -4,7 +4,7 @@ use std::cell::Cell;
 use std::os::raw::*;
 use std::ptr::null;
 
-use pyo3::ffi::PyObject;
+use pyo3::ffi::{self, PyObject, PyTypeObject};
 
 use crate::npyffi::*;
 
@@ -44,6 +44,13 @@ impl PyUFuncAPI {
     }
 }
 
+impl PyUFuncAPI {
+    /// Get a pointer of the type object of NumPy's `ufunc` class.
+    pub unsafe fn get_type_object(&self, py: Python) -> *mut PyTypeObject {
+        *(self.get(py, 0)) as _
+    }
+}
+
 impl PyUFuncAPI {
     impl_api![1; PyUFunc_FromFuncAndData(func: *mut PyUFuncGenericFunction, data: *mut *mut c_void, types: *mut c_char, ntypes: c_int, nin: c_int, nout: c_int, identity: c_int, name: *const c_char, doc: *const c_char, unused: c_int) -> *mut PyObject];
     impl_api![2; PyUFunc_RegisterLoopForType(ufunc: *mut PyUFuncObject, usertype: c_int, function: PyUFuncGenericFunction, arg_types: *mut c_int, data: *mut c_void) -> c_int];
@@ -101,3 +108,9 @@ impl PyUFuncAPI {
         identity_value: *const c_char,
     ) -> c_int];
 }
+
+/// Checks that `op` is an instance of `PyUFuncObject` or not.
+#[allow(non_snake_case)]
+pub unsafe fn PyUFunc_Check(py: Python, op: *mut PyObject) -> c_int {
+    ffi::PyObject_TypeCheck(op, PY_UFUNC_API.get_type_object(py))
+}
@@ -0,0 +1,82 @@
+//! Element types of the results of reductions like [`PyArray::sum`][crate::PyArray::sum] or [`PyArray::mean`][crate::PyArray::mean]
+
+#[cfg(feature = "half")]
+use half::f16;
+
+use crate::dtype::{Complex32, Complex64, Element};
+
+/// Element types which can be summed and multiplied by NumPy.
+///
+/// Following NumPy's type promotion rules, sums and products of booleans and small integers
+/// are accumulated in the widest integer type of the same signedness
+/// whereas floating point and complex numbers keep their type.
+///
+/// The accumulator type is passed to NumPy explicitly, so the results do not depend on
+/// the size of C's `long` which NumPy would use by default.
+pub trait Accumulate: Element {
+    /// The element type of sums and products.
+    type Output: Element;
+}
+
+/// Element types whose mean and standard deviation can be computed by NumPy.
+///
+/// Following NumPy's type promotion rules, integers are averaged as `f64`
+/// whereas floating point and complex numbers keep their type.
+/// The standard deviation of complex numbers is a real number of the same precision.
+pub trait Average: Element {
+    /// The element type of means.
+    type Mean: Element;
+    /// The element type of standard deviations.
+    type Deviation: Element;
+}
+
+macro_rules! impl_accumulate {
+    ($($ty:ty => $output:ty),+) => {
+        $(
+            impl Accumulate for $ty {
+                type Output = $output;
+            }
+        )+
+    };
+}
+
+impl_accumulate!(bool => i64);
+
+impl_accumulate!(i8 => i64, i16 => i64, i32 => i64, i64 => i64);
+impl_accumulate!(u8 => u64, u16 => u64, u32 => u64, u64 => u64);
+
+#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+impl_accumulate!(isize => isize, usize => usize);
+
+impl_accumulate!(f32 => f32, f64 => f64);
+
+#[cfg(feature = "half")]
+impl_accumulate!(f16 => f16);
+
+impl_accumulate!(Complex32 => Complex32, Complex64 => Complex64);
+
+macro_rules! impl_average {
+    ($($ty:ty => ($mean:ty, $deviation:ty)),+) => {
+        $(
+            impl Average for $ty {
+                type Mean = $mean;
+                type Deviation = $deviation;
+            }
+        )+
+    };
+}
+
+impl_average!(bool => (f64, f64));
+
+impl_average!(i8 => (f64, f64), i16 => (f64, f64), i32 => (f64, f64), i64 => (f64, f64));
+impl_average!(u8 => (f64, f64), u16 => (f64, f64), u32 => (f64, f64), u64 => (f64, f64));
+
+#[cfg(any(target_pointer_width = "32", target_pointer_width = "64"))]
+impl_average!(isize => (f64, f64), usize => (f64, f64));
+
+impl_average!(f32 => (f32, f32), f64 => (f64, f64));
+
+#[cfg(feature = "half")]
+impl_average!(f16 => (f16, f16));
+
+impl_average!(Complex32 => (Complex32, f32), Complex64 => (Complex64, f64));
@@ -0,0 +1,1119 @@
+//! Safe interface for calling and creating NumPy's [universal functions][ufuncs] from Rust code
+//!
+//! [ufuncs]: https://numpy.org/doc/stable/reference/ufuncs.html
+
+use std::any::Any;
+use std::ffi::CString;
+use std::fmt;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process;
+use std::ptr;
+use std::slice;
+
+use ndarray::{
+    ArrayBase, ArrayView, ArrayViewMut, Axis, Dim, Dimension, IxDyn, RawData, RemoveAxis,
+    ShapeBuilder, StrideShape,
+};
+use pyo3::{
+    exceptions::PyValueError,
+    ffi, pyclass, pyobject_native_type_extract, pyobject_native_type_named,
+    types::{PyDict, PyModule, PyTuple},
+    AsPyPointer, IntoPy, IntoPyPointer, Py, PyAny, PyErr, PyResult, PyTypeInfo, Python, ToPyObject,
+};
+
+use crate::array::PyArray;
+use crate::borrow::PyReadwriteArray;
+use crate::dtype::{Element, PyArrayDescr};
+use crate::error::DIMENSIONALITY_MISMATCH_ERR;
+use crate::npyffi::{
+    npy_intp, PyUFuncGenericFunction, PyUFuncObject, PyUFunc_Check, NPY_TYPES, PY_UFUNC_API,
+};
+
+/// Value of `identity` used for ufuncs without an identity element, cf. `PyUFunc_None`.
+const PY_UFUNC_NONE: c_int = -1;
+
+/// A safe wrapper for NumPy's [`ufunc`][ufunc] class.
+///
+/// Existing ufuncs like `np.add` can be obtained using [`from_numpy`][Self::from_numpy]
+/// whereas new ones are created using [`PyUFuncBuilder`] and [`PyGUFuncBuilder`].
+///
+/// The methods calling the ufunc take the element type `R` of their result as a type parameter
+/// which is passed to NumPy as the `dtype` argument, i.e. NumPy will select a loop producing `R`
+/// and fail with a [`TypeError`][pyo3::exceptions::PyTypeError] if there is none.
+/// Only ufuncs with a single output are supported.
+///
+/// # Example
+///
+/// ```
+/// use ndarray::Axis;
+/// use numpy::{PyArray, PyArray1, PyArray2, PyUFunc};
+/// use pyo3::Python;
+///
+/// Python::with_gil(|py| {
+///     let add = PyUFunc::from_numpy(py, "add").unwrap();
+///
+///     let a = PyArray::arange(py, 0, 6, 1).reshape([2, 3]).unwrap();
+///
+///     let b: &PyArray2<f64> = add.call((a, 0.5)).unwrap();
+///     assert_eq!(b.readonly().as_slice().unwrap(), [0.5, 1.5, 2.5, 3.5, 4.5, 5.5]);
+///
+///     let c: &PyArray1<i64> = add.reduce(a, Axis(0)).unwrap();
+///     assert_eq!(c.readonly().as_slice().unwrap(), [3, 5, 7]);
+///
+///     let d: &PyArray1<i64> = add.accumulate(c, Axis(0)).unwrap();
+///     assert_eq!(d.readonly().as_slice().unwrap(), [3, 8, 15]);
+/// });
+/// ```
+///
+/// [ufunc]: https://numpy.org/doc/stable/reference/ufuncs.html
+#[repr(transparent)]
+pub struct PyUFunc(PyAny);
+
+pyobject_native_type_named!(PyUFunc);
+
+unsafe impl PyTypeInfo for PyUFunc {
+    type AsRefTarget = Self;
+
+    const NAME: &'static str = "PyUFunc";
+    const MODULE: Option<&'static str> = Some("numpy");
+
+    #[inline]
+    fn type_object_raw(py: Python) -> *mut ffi::PyTypeObject {
+        unsafe { PY_UFUNC_API.get_type_object(py) }
+    }
+
+    fn is_type_of(ob: &PyAny) -> bool {
+        unsafe { PyUFunc_Check(ob.py(), ob.as_ptr()) != 0 }
+    }
+}
+
+pyobject_native_type_extract!(PyUFunc);
+
+impl PyUFunc {
+    /// Looks up the ufunc with the given `name` in the `numpy` module, e.g. `"add"` or `"logaddexp"`.
+    ///
+    /// Fails if there is no such attribute or if it is not a ufunc.
+    pub fn from_numpy<'py>(py: Python<'py>, name: &str) -> PyResult<&'py Self> {
+        let ufunc = PyModule::import(py, "numpy")?.getattr(name)?;
+        Ok(ufunc.downcast()?)
+    }
+
+    /// Returns a raw pointer to the underlying [`PyUFuncObject`].
+    #[inline]
+    pub fn as_ufunc_ptr(&self) -> *mut PyUFuncObject {
+        self.as_ptr() as _
+    }
+
+    /// Returns the number of inputs of the ufunc.
+    pub fn nin(&self) -> usize {
+        unsafe { (*self.as_ufunc_ptr()).nin as usize }
+    }
+
+    /// Returns the number of outputs of the ufunc.
+    pub fn nout(&self) -> usize {
+        unsafe { (*self.as_ufunc_ptr()).nout as usize }
+    }
+
+    /// Calls the ufunc with the given arguments, returning a new array.
+    ///
+    /// The dimensionality `D` of the result is checked at runtime.
+    /// Note that NumPy returns scalars instead of zero-dimensional arrays
+    /// if all arguments are scalars, so that extracting the result will fail.
+    ///
+    /// See also [`ufunc.__call__`][ufunc-call].
+    ///
+    /// [ufunc-call]: https://numpy.org/doc/stable/reference/generated/numpy.ufunc.__call__.html
+    pub fn call<'py, A, R, D>(&'py self, args: A) -> PyResult<&'py PyArray<R, D>>
+    where
+        A: IntoPy<Py<PyTuple>>,
+        R: Element,
+        D: Dimension,
+    {
+        let kwargs = self.kwargs::<R>()?;
+
+        PyAny::call(self, args, Some(kwargs))?.extract()
+    }
+
+    /// Calls the ufunc with the given arguments, writing the result into `out`.
+    ///
+    /// The arguments are broadcast to the shape of `out` and the result is cast to its element type
+    /// if that is possible using [`same_kind`][crate::array::Casting::SameKind] casting.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{PyArray, PyUFunc};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let maximum = PyUFunc::from_numpy(py, "maximum").unwrap();
+    ///
+    ///     let a = PyArray::from_slice(py, &[1.0, 5.0, 3.0]);
+    ///     let b = PyArray::from_slice(py, &[4.0, 2.0, 6.0]);
+    ///
+    ///     let mut a_rw = a.readwrite();
+    ///     maximum.call_out((a, b), &mut a_rw).unwrap();
+    ///     assert_eq!(a_rw.as_slice().unwrap(), [4.0, 5.0, 6.0]);
+    /// });
+    /// ```
+    pub fn call_out<A, R, D>(&self, args: A, out: &mut PyReadwriteArray<R, D>) -> PyResult<()>
+    where
+        A: IntoPy<Py<PyTuple>>,
+        R: Element,
+        D: Dimension,
+    {
+        let out: &PyArray<R, D> = out;
+
+        let kwargs = PyDict::new(self.py());
+        kwargs.set_item("out", (out,))?;
+
+        PyAny::call(self, args, Some(kwargs))?;
+        Ok(())
+    }
+
+    /// Reduces the given `array` along `axis` by repeatedly applying the ufunc.
+    ///
+    /// See also [`ufunc.reduce`][ufunc-reduce].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `axis` is out of bounds.
+    ///
+    /// [ufunc-reduce]: https://numpy.org/doc/stable/reference/generated/numpy.ufunc.reduce.html
+    pub fn reduce<'py, R, T, D>(
+        &'py self,
+        array: &PyArray<T, D>,
+        axis: Axis,
+    ) -> PyResult<&'py PyArray<R, D::Smaller>>
+    where
+        R: Element,
+        T: Element,
+        D: RemoveAxis,
+    {
+        let dims = array.dims_without(axis);
+
+        // SAFETY: The reduction will overwrite all elements of `out`.
+        let out = unsafe { PyArray::<R, _>::new(self.py(), dims, false) };
+
+        // Passing `out` explicitly ensures that an array is returned
+        // even if the reduction yields a scalar.
+        let kwargs = self.kwargs::<R>()?;
+        kwargs.set_item("axis", axis.index())?;
+        kwargs.set_item("out", (out,))?;
+
+        self.call_method("reduce", (array,), Some(kwargs))?;
+        Ok(out)
+    }
+
+    /// Accumulates the result of applying the ufunc to the given `array` along `axis`.
+    ///
+    /// See also [`ufunc.accumulate`][ufunc-accumulate].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `axis` is out of bounds.
+    ///
+    /// [ufunc-accumulate]: https://numpy.org/doc/stable/reference/generated/numpy.ufunc.accumulate.html
+    pub fn accumulate<'py, R, T, D>(
+        &'py self,
+        array: &PyArray<T, D>,
+        axis: Axis,
+    ) -> PyResult<&'py PyArray<R, D>>
+    where
+        R: Element,
+        T: Element,
+        D: Dimension,
+    {
+        array.check_axis(axis);
+
+        let kwargs = self.kwargs::<R>()?;
+        kwargs.set_item("axis", axis.index())?;
+
+        self.call_method("accumulate", (array,), Some(kwargs))?
+            .extract()
+    }
+
+    /// Reduces the slices of the given `array` along `axis` starting at the given `indices`.
+    ///
+    /// See also [`ufunc.reduceat`][ufunc-reduceat].
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `axis` is out of bounds.
+    ///
+    /// [ufunc-reduceat]: https://numpy.org/doc/stable/reference/generated/numpy.ufunc.reduceat.html
+    pub fn reduceat<'py, R, T, D>(
+        &'py self,
+        array: &PyArray<T, D>,
+        indices: &[usize],
+        axis: Axis,
+    ) -> PyResult<&'py PyArray<R, D>>
+    where
+        R: Element,
+        T: Element,
+        D: Dimension,
+    {
+        array.check_axis(axis);
+
+        let indices = indices.to_object(self.py());
+
+        let kwargs = self.kwargs::<R>()?;
+        kwargs.set_item("axis", axis.index())?;
+
+        self.call_method("reduceat", (array, indices), Some(kwargs))?
+            .extract()
+    }
+
+    /// Applies the ufunc to all pairs of elements of `a` and `b`.
+    ///
+    /// The result has the dimensions of `a` followed by those of `b`.
+    ///
+    /// See also [`ufunc.outer`][ufunc-outer].
+    ///
+    /// [ufunc-outer]: https://numpy.org/doc/stable/reference/generated/numpy.ufunc.outer.html
+    pub fn outer<'py, R, T1, D1, T2, D2>(
+        &'py self,
+        a: &PyArray<T1, D1>,
+        b: &PyArray<T2, D2>,
+    ) -> PyResult<&'py PyArray<R, IxDyn>>
+    where
+        R: Element,
+    {
+        let kwargs = self.kwargs::<R>()?;
+
+        self.call_method("outer", (a, b), Some(kwargs))?.extract()
+    }
+
+    /// Applies the unary ufunc in place to the elements of `a` at the given `indices` along its first axis.
+    ///
+    /// Unlike assigning to `a[indices]`, repeated indices are applied repeatedly.
+    ///
+    /// See also [`ufunc.at`][ufunc-at].
+    ///
+    /// [ufunc-at]: https://numpy.org/doc/stable/reference/generated/numpy.ufunc.at.html
+    pub fn at<T, D>(&self, a: &mut PyReadwriteArray<T, D>, indices: &[usize]) -> PyResult<()>
+    where
+        T: Element,
+        D: Dimension,
+    {
+        let a: &PyArray<T, D> = a;
+        let indices = indices.to_object(self.py());
+
+        self.call_method1("at", (a, indices))?;
+        Ok(())
+    }
+
+    /// Applies the binary ufunc in place to the elements of `a` at the given `indices` along its first axis
+    /// using the elements of `b` as the second argument.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{PyArray, PyUFunc};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///     let add = PyUFunc::from_numpy(py, "add").unwrap();
+    ///
+    ///     let histogram = PyArray::<i64, _>::zeros(py, 3, false);
+    ///     let ones = PyArray::from_slice(py, &[1_i64]);
+    ///
+    ///     add.at_with(&mut histogram.readwrite(), &[0, 2, 2, 1, 2], ones).unwrap();
+    ///     assert_eq!(histogram.readonly().as_slice().unwrap(), [1, 1, 3]);
+    /// });
+    /// ```
+    pub fn at_with<T, D, U, E>(
+        &self,
+        a: &mut PyReadwriteArray<T, D>,
+        indices: &[usize],
+        b: &PyArray<U, E>,
+    ) -> PyResult<()>
+    where
+        T: Element,
+        D: Dimension,
+    {
+        let a: &PyArray<T, D> = a;
+        let indices = indices.to_object(self.py());
+
+        self.call_method1("at", (a, indices, b))?;
+        Ok(())
+    }
+
+    /// Registers an element-wise function as the loop of this ufunc for its argument and return types.
+    ///
+    /// NumPy only allows adding loops which involve at least one user-defined or structured element type,
+    /// e.g. a `#[repr(C)]` struct using `#[derive(Element)]`. The function must take [`nin`][Self::nin] arguments
+    /// and the ufunc must have a single output.
+    ///
+    /// NumPy provides no way to remove a loop and might call it without holding the GIL at any time,
+    /// hence the function is leaked, i.e. it will be kept alive for the remainder of the process.
+    /// Panicking within the function aborts the process, cf. [`PyUFuncBuilder`].
+    ///
+    /// # Safety
+    ///
+    /// NumPy looks up user-defined loops only by the type numbers of their arguments and all structured element types
+    /// share the type number of `void`. Hence no other loop with the same type numbers may be registered for this ufunc,
+    /// neither by this crate nor by other extensions, as NumPy might otherwise call a loop using the memory layout
+    /// of a different structured element type. For example, registering loops `(A, A) -> A` and `(B, B) -> B`
+    /// for two distinct structured element types `A` and `B` is undefined behaviour.
+    pub unsafe fn register_loop<F, Args>(&self, f: F) -> PyResult<()>
+    where
+        F: ElementwiseFn<Args>,
+    {
+        let py = self.py();
+
+        if F::NIN != self.nin() || self.nout() != 1 {
+            return Err(PyValueError::new_err(format!(
+                "loops must take {} inputs and return a single output to match the ufunc",
+                self.nin()
+            )));
+        }
+
+        let dtypes = F::dtypes(py);
+
+        let user_dtype = dtypes
+            .iter()
+            .find(|dtype| is_user_dtype(dtype))
+            .ok_or_else(|| {
+                PyValueError::new_err(
+                    "loops can only be registered for user-defined or structured element types",
+                )
+            })?;
+
+        let mut arg_dtypes = dtypes
+            .iter()
+            .map(|dtype| dtype.as_dtype_ptr())
+            .collect::<Vec<_>>();
+
+        // NumPy copies the argument types but stores `data` as is.
+        let data = Box::into_raw(Box::new(f)) as *mut c_void;
+
+        let res = PY_UFUNC_API.PyUFunc_RegisterLoopForDescr(
+            py,
+            self.as_ufunc_ptr(),
+            user_dtype.as_dtype_ptr(),
+            Some(elementwise_loop::<F, Args>),
+            arg_dtypes.as_mut_ptr(),
+            data,
+        );
+
+        if res == 0 {
+            Ok(())
+        } else {
+            // NumPy did not store the loop, hence the function can be dropped.
+            drop(Box::from_raw(data as *mut F));
+
+            Err(PyErr::fetch(py))
+        }
+    }
+
+    fn kwargs<R: Element>(&self) -> PyResult<&PyDict> {
+        let kwargs = PyDict::new(self.py());
+        kwargs.set_item("dtype", R::get_dtype(self.py()))?;
+        Ok(kwargs)
+    }
+}
+
+/// Builds a NumPy [universal function][ufuncs] from element-wise Rust functions.
+///
+/// Each loop added via [`add_loop`][Self::add_loop] handles one combination of element types,
+/// e.g. `|x: f64, y: f64| x.hypot(y)` handles two `float64` inputs producing a `float64` output.
+/// NumPy selects the first loop to which the arguments can be safely cast
+/// and takes care of broadcasting as well as the `out`, `where` and `dtype` keyword arguments.
+///
+/// All loops must take the same number of inputs and return a single output.
+/// Only boolean, integer, floating point and complex element types are supported.
+///
+/// Note that NumPy may call the loops without holding the GIL, hence they must be `Send + Sync`.
+/// As NumPy does not check for Python exceptions after calling these loops, they must not panic.
+/// A panic is reported by the panic hook and then aborts the process.
+///
+/// # Example
+///
+/// ```
+/// use numpy::PyUFuncBuilder;
+/// use pyo3::{py_run, Python};
+///
+/// Python::with_gil(|py| {
+///     let clamp = PyUFuncBuilder::new(py, "clamp")
+///         .doc("Clamps the first argument to the range given by the other two.")
+///         .add_loop(|x: i64, lo: i64, hi: i64| x.max(lo).min(hi))
+///         .add_loop(|x: f64, lo: f64, hi: f64| x.max(lo).min(hi))
+///         .build()
+///         .unwrap();
+///
+///     py_run!(py, clamp, r#"
+/// import numpy as np
+///
+/// assert clamp.nin == 3
+/// assert clamp.types == ["lll->l", "ddd->d"]
+///
+/// assert np.array_equal(clamp([-2, 0, 2], -1, 1), [-1, 0, 1])
+/// assert clamp(np.arange(4.0), 0.5, 2.5).tolist() == [0.5, 1.0, 2.0, 2.5]
+/// "#);
+/// });
+/// ```
+///
+/// [ufuncs]: https://numpy.org/doc/stable/reference/ufuncs.html
+pub struct PyUFuncBuilder<'py> {
+    py: Python<'py>,
+    name: String,
+    doc: String,
+    nin: Option<usize>,
+    loops: Vec<Loop>,
+}
+
+struct Loop {
+    function: PyUFuncGenericFunction,
+    data: *mut c_void,
+    kernel: Box<dyn Any + Send + Sync>,
+    types: Vec<c_char>,
+    error: Option<String>,
+}
+
+impl<'py> PyUFuncBuilder<'py> {
+    /// Starts building a ufunc with the given `name` which is used by its `__name__` and `repr`.
+    pub fn new(py: Python<'py>, name: &str) -> Self {
+        Self {
+            py,
+            name: name.to_owned(),
+            doc: String::new(),
+            nin: None,
+            loops: Vec::new(),
+        }
+    }
+
+    /// Sets the docstring of the ufunc.
+    pub fn doc(mut self, doc: &str) -> Self {
+        self.doc = doc.to_owned();
+        self
+    }
+
+    /// Adds an element-wise function as the loop for its argument and return types.
+    ///
+    /// Loops are tried in the order they were added.
+    pub fn add_loop<F, Args>(mut self, f: F) -> Self
+    where
+        F: ElementwiseFn<Args>,
+    {
+        let kernel = Box::new(f);
+        let data = &*kernel as *const F as *mut c_void;
+
+        let (types, error) = loop_types(F::dtypes(self.py));
+
+        self.nin.get_or_insert(F::NIN);
+
+        self.loops.push(Loop {
+            function: Some(elementwise_loop::<F, Args>),
+            data,
+            kernel,
+            types,
+            error,
+        });
+        self
+    }
+
+    /// Creates the ufunc which keeps the loops alive as long as it is referenced.
+    ///
+    /// Fails if no loops were added, if the loops take differing numbers of inputs
+    /// or use unsupported element types, or if the name or docstring contain NUL bytes.
+    pub fn build(self) -> PyResult<&'py PyUFunc> {
+        let nin = self
+            .nin
+            .ok_or_else(|| PyValueError::new_err("a ufunc requires at least one loop"))?;
+
+        create_ufunc(self.py, self.name, self.doc, nin, self.loops, None)
+    }
+}
+
+impl fmt::Debug for PyUFuncBuilder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PyUFuncBuilder")
+            .field("name", &self.name)
+            .field("nin", &self.nin)
+            .field("loops", &self.loops.len())
+            .finish()
+    }
+}
+
+/// Builds a NumPy [generalized universal function][gufuncs] from Rust functions operating on core dimensions.
+///
+/// The `signature` determines the core dimensions of the inputs and the output, e.g. `(m,n),(n)->(m)`
+/// for a matrix-vector product. NumPy broadcasts all other, i.e. leading, dimensions and calls the loops
+/// with [`ArrayView`] and [`ArrayViewMut`] of the core dimensions for each element of the broadcast shape.
+///
+/// The dimensionalities of the views must match the number of core dimensions given in the signature,
+/// but [`IxDyn`][type@ndarray::IxDyn] can be used to accept any number. Otherwise, the same restrictions
+/// as for [`PyUFuncBuilder`] apply, i.e. there must be a single output, loops must be `Send + Sync`
+/// and panicking within a loop aborts the process.
+///
+/// # Example
+///
+/// ```
+/// use ndarray::{ArrayView1, ArrayView2, ArrayViewMut1};
+/// use numpy::PyGUFuncBuilder;
+/// use pyo3::{py_run, Python};
+///
+/// Python::with_gil(|py| {
+///     let matvec = PyGUFuncBuilder::new(py, "matvec", "(m,n),(n)->(m)")
+///         .add_loop(|a: ArrayView2<f64>, x: ArrayView1<f64>, mut y: ArrayViewMut1<f64>| {
+///             y.assign(&a.dot(&x));
+///         })
+///         .build()
+///         .unwrap();
+///
+///     py_run!(py, matvec, r#"
+/// import numpy as np
+///
+/// assert matvec.signature == "(m,n),(n)->(m)"
+///
+/// a = np.arange(12.0).reshape(2, 2, 3)
+/// x = np.ones(3)
+/// assert np.array_equal(matvec(a, x), a @ x)
+/// "#);
+/// });
+/// ```
+///
+/// [gufuncs]: https://numpy.org/doc/stable/reference/c-api/generalized-ufuncs.html
+pub struct PyGUFuncBuilder<'py> {
+    py: Python<'py>,
+    name: String,
+    doc: String,
+    signature: String,
+    core_dims: Result<CoreDims, String>,
+    loops: Vec<Loop>,
+}
+
+impl<'py> PyGUFuncBuilder<'py> {
+    /// Starts building a gufunc with the given `name` and core dimension `signature`.
+    pub fn new(py: Python<'py>, name: &str, signature: &str) -> Self {
+        Self {
+            py,
+            name: name.to_owned(),
+            doc: String::new(),
+            signature: signature.to_owned(),
+            core_dims: CoreDims::parse(signature),
+            loops: Vec::new(),
+        }
+    }
+
+    /// Sets the docstring of the gufunc.
+    pub fn doc(mut self, doc: &str) -> Self {
+        self.doc = doc.to_owned();
+        self
+    }
+
+    /// Adds a function operating on views of the core dimensions as the loop for its element types.
+    ///
+    /// Loops are tried in the order they were added.
+    pub fn add_loop<F, Args>(mut self, f: F) -> Self
+    where
+        F: CoreFn<Args>,
+    {
+        let (types, mut error) = loop_types(F::dtypes(self.py));
+
+        let args = match &self.core_dims {
+            Ok(core_dims) => {
+                let ndims = F::ndims();
+
+                if ndims.len() == core_dims.args.len() {
+                    for (ndim, dims) in ndims.iter().zip(&core_dims.args) {
+                        if ndim.map_or(false, |ndim| ndim != dims.len()) {
+                            error.get_or_insert_with(|| {
+                                format!(
+                                    "loop dimensionalities {:?} do not match signature {}",
+                                    ndims, self.signature
+                                )
+                            });
+                        }
+                    }
+                }
+
+                core_dims.args.clone()
+            }
+            Err(_) => Vec::new(),
+        };
+
+        let kernel = Box::new(CoreKernel { f, args });
+        let data = &*kernel as *const CoreKernel<F> as *mut c_void;
+
+        self.loops.push(Loop {
+            function: Some(core_loop::<F, Args>),
+            data,
+            kernel,
+            types,
+            error,
+        });
+        self
+    }
+
+    /// Creates the gufunc which keeps the loops alive as long as it is referenced.
+    ///
+    /// Fails if the signature is invalid or does not have a single output, if no loops were added,
+    /// if the loops do not match the signature or use unsupported element types,
+    /// or if the name or docstring contain NUL bytes.
+    pub fn build(self) -> PyResult<&'py PyUFunc> {
+        let core_dims = self.core_dims.map_err(PyValueError::new_err)?;
+
+        create_ufunc(
+            self.py,
+            self.name,
+            self.doc,
+            core_dims.args.len() - 1,
+            self.loops,
+            Some(self.signature),
+        )
+    }
+}
+
+impl fmt::Debug for PyGUFuncBuilder<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PyGUFuncBuilder")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .field("loops", &self.loops.len())
+            .finish()
+    }
+}
+
+/// The core dimensions of the arguments of a gufunc as indices into the distinct dimension names
+#[derive(Clone)]
+struct CoreDims {
+    args: Vec<Vec<usize>>,
+}
+
+impl CoreDims {
+    fn parse(signature: &str) -> Result<Self, String> {
+        let invalid = || format!("invalid gufunc signature {}", signature);
+
+        let signature = signature
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>();
+
+        let arrow = signature.find("->").ok_or_else(invalid)?;
+
+        let mut names = Vec::new();
+        let mut args = parse_core_dims(&signature[..arrow], &mut names).ok_or_else(invalid)?;
+        let outputs = parse_core_dims(&signature[arrow + 2..], &mut names).ok_or_else(invalid)?;
+
+        if outputs.len() != 1 {
+            return Err(format!(
+                "gufuncs must have a single output but the signature {} has {}",
+                signature,
+                outputs.len()
+            ));
+        }
+
+        args.extend(outputs);
+
+        Ok(Self { args })
+    }
+}
+
+fn parse_core_dims<'a>(mut args: &'a str, names: &mut Vec<&'a str>) -> Option<Vec<Vec<usize>>> {
+    let mut core_dims = Vec::new();
+
+    loop {
+        args = args.strip_prefix('(')?;
+        let end = args.find(')')?;
+
+        let mut dims = Vec::new();
+
+        if end != 0 {
+            for name in args[..end].split(',') {
+                let name = name.trim_end_matches('?');
+                if name.is_empty() {
+                    return None;
+                }
+
+                let idx = names.iter().position(|&other| other == name);
+                dims.push(idx.unwrap_or_else(|| {
+                    names.push(name);
+                    names.len() - 1
+                }));
+            }
+        }
+
+        core_dims.push(dims);
+
+        args = &args[end + 1..];
+        if args.is_empty() {
+            return Some(core_dims);
+        }
+        args = args.strip_prefix(',')?;
+    }
+}
+
+fn create_ufunc<'py>(
+    py: Python<'py>,
+    name: String,
+    doc: String,
+    nin: usize,
+    loops: Vec<Loop>,
+    signature: Option<String>,
+) -> PyResult<&'py PyUFunc> {
+    if loops.is_empty() {
+        return Err(PyValueError::new_err("a ufunc requires at least one loop"));
+    }
+
+    let mut functions = Vec::with_capacity(loops.len());
+    let mut data = Vec::with_capacity(loops.len());
+    let mut kernels = Vec::with_capacity(loops.len());
+    let mut types = Vec::with_capacity(loops.len() * (nin + 1));
+
+    for loop_ in loops {
+        if loop_.types.len() != nin + 1 {
+            return Err(PyValueError::new_err(format!(
+                "all loops of a ufunc must take {} inputs",
+                nin
+            )));
+        }
+
+        if let Some(err) = loop_.error {
+            return Err(PyValueError::new_err(err));
+        }
+
+        functions.push(loop_.function);
+        data.push(loop_.data);
+        kernels.push(loop_.kernel);
+        types.extend(loop_.types);
+    }
+
+    let name = CString::new(name)?;
+    let doc = CString::new(doc)?;
+    let signature = signature.map(CString::new).transpose()?;
+
+    // NumPy does not copy these arrays but stores pointers to them in the ufunc object,
+    // so they are moved onto the Python heap and attached to the ufunc below.
+    let mut loops = PyUFuncLoops {
+        functions: functions.into_boxed_slice(),
+        data: data.into_boxed_slice(),
+        types: types.into_boxed_slice(),
+        _kernels: kernels,
+        name,
+        doc,
+    };
+
+    let ptr = unsafe {
+        PY_UFUNC_API.PyUFunc_FromFuncAndDataAndSignature(
+            py,
+            loops.functions.as_mut_ptr(),
+            loops.data.as_mut_ptr(),
+            loops.types.as_mut_ptr(),
+            loops.functions.len() as c_int,
+            nin as c_int,
+            1,
+            PY_UFUNC_NONE,
+            loops.name.as_ptr(),
+            loops.doc.as_ptr(),
+            0,
+            signature
+                .as_ref()
+                .map_or(ptr::null(), |signature| signature.as_ptr()),
+        )
+    };
+
+    let ufunc = unsafe { py.from_owned_ptr_or_err::<PyUFunc>(ptr)? };
+
+    // SAFETY: The ufunc is newly created and releases its `obj` when it is deallocated.
+    unsafe {
+        (*(ufunc.as_ptr() as *mut PyUFuncObject)).obj = loops.into_py(py).into_ptr();
+    }
+
+    Ok(ufunc)
+}
+
+fn loop_types(dtypes: Vec<&PyArrayDescr>) -> (Vec<c_char>, Option<String>) {
+    let supported = dtypes.iter().all(|dtype| {
+        !dtype.has_subarray() && matches!(dtype.kind(), b'b' | b'i' | b'u' | b'f' | b'c')
+    });
+
+    let error = if supported {
+        None
+    } else {
+        Some(
+            "ufunc loops only support boolean, integer, floating point and complex element types"
+                .to_owned(),
+        )
+    };
+
+    let types = dtypes
+        .into_iter()
+        .map(|dtype| dtype.num() as c_char)
+        .collect();
+
+    (types, error)
+}
+
+fn is_user_dtype(dtype: &PyArrayDescr) -> bool {
+    let num = dtype.num();
+
+    num >= NPY_TYPES::NPY_USERDEF as c_int
+        || (num == NPY_TYPES::NPY_VOID as c_int && dtype.has_fields())
+}
+
+/// Utility type to store the loops of a ufunc on the Python heap
+#[pyclass]
+struct PyUFuncLoops {
+    functions: Box<[PyUFuncGenericFunction]>,
+    data: Box<[*mut c_void]>,
+    types: Box<[c_char]>,
+    _kernels: Vec<Box<dyn Any + Send + Sync>>,
+    name: CString,
+    doc: CString,
+}
+
+unsafe impl Send for PyUFuncLoops {}
+
+mod sealed {
+    pub trait Sealed<Args> {}
+}
+
+/// Element-wise functions which can be used as ufunc loops, cf. [`PyUFuncBuilder::add_loop`].
+///
+/// This is implemented for closures and functions taking one to four arguments of
+/// boolean, integer, floating point or complex element types and returning a value of such a type.
+pub trait ElementwiseFn<Args>: sealed::Sealed<Args> + Send + Sync + 'static {
+    #[doc(hidden)]
+    const NIN: usize;
+
+    #[doc(hidden)]
+    fn dtypes<'py>(py: Python<'py>) -> Vec<&'py PyArrayDescr>;
+
+    /// # Safety
+    ///
+    /// `args` and `steps` must describe `NIN + 1` strided arrays of length `len`
+    /// of the element types returned by `dtypes`.
+    #[doc(hidden)]
+    unsafe fn call_strided(&self, args: &[*mut c_char], steps: &[npy_intp], len: npy_intp);
+}
+
+macro_rules! impl_elementwise_fn {
+    ($nin:literal; $($arg:ident: $ty:ident),+) => {
+        impl<F, $($ty,)+ R> sealed::Sealed<($($ty,)+)> for F where F: Fn($($ty),+) -> R {}
+
+        impl<F, $($ty,)+ R> ElementwiseFn<($($ty,)+)> for F
+        where
+            F: Fn($($ty),+) -> R + Send + Sync + 'static,
+            $($ty: Element + Copy,)+
+            R: Element + Copy,
+        {
+            const NIN: usize = $nin;
+
+            fn dtypes<'py>(py: Python<'py>) -> Vec<&'py PyArrayDescr> {
+                vec![$($ty::get_dtype(py),)+ R::get_dtype(py)]
+            }
+
+            unsafe fn call_strided(&self, args: &[*mut c_char], steps: &[npy_intp], len: npy_intp) {
+                for idx in 0..len {
+                    let mut pos = 0;
+                    $(
+                        let $arg = ptr::read_unaligned(args[pos].offset(idx * steps[pos]) as *const $ty);
+                        pos += 1;
+                    )+
+
+                    let res = self($($arg),+);
+                    ptr::write_unaligned(args[pos].offset(idx * steps[pos]) as *mut R, res);
+                }
+            }
+        }
+    };
+}
+
+impl_elementwise_fn!(1; a: A);
+impl_elementwise_fn!(2; a: A, b: B);
+impl_elementwise_fn!(3; a: A, b: B, c: C);
+impl_elementwise_fn!(4; a: A, b: B, c: C, d: D);
+
+unsafe extern "C" fn elementwise_loop<F, Args>(
+    args: *mut *mut c_char,
+    dimensions: *mut npy_intp,
+    steps: *mut npy_intp,
+    data: *mut c_void,
+) where
+    F: ElementwiseFn<Args>,
+{
+    let kernel = &*(data as *const F);
+    let args = slice::from_raw_parts(args, F::NIN + 1);
+    let steps = slice::from_raw_parts(steps, F::NIN + 1);
+    let len = *dimensions;
+
+    abort_on_panic(|| kernel.call_strided(args, steps, len));
+}
+
+/// Functions operating on the core dimensions which can be used as gufunc loops, cf. [`PyGUFuncBuilder::add_loop`].
+///
+/// This is implemented for closures and functions taking one to three [`ArrayView`] of the inputs
+/// and an [`ArrayViewMut`] of the output of boolean, integer, floating point or complex element types.
+pub trait CoreFn<Args>: sealed::Sealed<Args> + Send + Sync + 'static {
+    #[doc(hidden)]
+    const NIN: usize;
+
+    #[doc(hidden)]
+    fn dtypes<'py>(py: Python<'py>) -> Vec<&'py PyArrayDescr>;
+
+    #[doc(hidden)]
+    fn ndims() -> Vec<Option<usize>>;
+
+    /// # Safety
+    ///
+    /// `ptrs`, `shapes` and `strides` must describe `NIN + 1` non-overlapping arrays
+    /// of the element types returned by `dtypes` and the dimensionalities returned by `ndims`.
+    #[doc(hidden)]
+    unsafe fn call(&self, ptrs: &[*mut c_char], shapes: &[Vec<usize>], strides: &[&[npy_intp]]);
+}
+
+macro_rules! impl_core_fn {
+    ($nin:literal; $($arg:ident: $ty:ident, $dim:ident),+) => {
+        impl<F, $($ty, $dim,)+ R, DR> sealed::Sealed<fn($(ArrayView<$ty, $dim>,)+ ArrayViewMut<R, DR>)> for F
+        where
+            F: Fn($(ArrayView<$ty, $dim>,)+ ArrayViewMut<R, DR>),
+        {
+        }
+
+        impl<F, $($ty, $dim,)+ R, DR> CoreFn<fn($(ArrayView<$ty, $dim>,)+ ArrayViewMut<R, DR>)> for F
+        where
+            F: Fn($(ArrayView<$ty, $dim>,)+ ArrayViewMut<R, DR>) + Send + Sync + 'static,
+            $($ty: Element + Copy, $dim: Dimension,)+
+            R: Element + Copy,
+            DR: Dimension,
+        {
+            const NIN: usize = $nin;
+
+            fn dtypes<'py>(py: Python<'py>) -> Vec<&'py PyArrayDescr> {
+                vec![$($ty::get_dtype(py),)+ R::get_dtype(py)]
+            }
+
+            fn ndims() -> Vec<Option<usize>> {
+                vec![$($dim::NDIM,)+ DR::NDIM]
+            }
+
+            unsafe fn call(&self, ptrs: &[*mut c_char], shapes: &[Vec<usize>], strides: &[&[npy_intp]]) {
+                let mut pos = 0;
+                $(
+                    let $arg = core_view(ptrs[pos], &shapes[pos], strides[pos], |shape, ptr| ArrayView::<$ty, $dim>::from_shape_ptr(shape, ptr));
+                    pos += 1;
+                )+
+
+                let out = core_view(ptrs[pos], &shapes[pos], strides[pos], |shape, ptr| ArrayViewMut::<R, DR>::from_shape_ptr(shape, ptr));
+
+                self($($arg,)+ out);
+            }
+        }
+    };
+}
+
+impl_core_fn!(1; a: A, DA);
+impl_core_fn!(2; a: A, DA, b: B, DB);
+impl_core_fn!(3; a: A, DA, b: B, DB, c: C, DC);
+
+struct CoreKernel<F> {
+    f: F,
+    args: Vec<Vec<usize>>,
+}
+
+unsafe extern "C" fn core_loop<F, Args>(
+    args: *mut *mut c_char,
+    dimensions: *mut npy_intp,
+    steps: *mut npy_intp,
+    data: *mut c_void,
+) where
+    F: CoreFn<Args>,
+{
+    let kernel = &*(data as *const CoreKernel<F>);
+    let nargs = F::NIN + 1;
+
+    // The layout of `dimensions` and `steps` is described in NumPy's documentation
+    // on the implementation of generalized ufuncs.
+    let len = *dimensions;
+    let num_dims = kernel.args.iter().flatten().max().map_or(0, |&idx| idx + 1);
+    let dims = slice::from_raw_parts(dimensions.offset(1), num_dims);
+
+    let num_core_steps = kernel.args.iter().map(Vec::len).sum::<usize>();
+    let steps = slice::from_raw_parts(steps, nargs + num_core_steps);
+    let (outer_steps, mut core_steps) = steps.split_at(nargs);
+
+    let mut shapes = Vec::with_capacity(nargs);
+    let mut strides = Vec::with_capacity(nargs);
+
+    for arg in &kernel.args {
+        shapes.push(
+            arg.iter()
+                .map(|&idx| dims[idx] as usize)
+                .collect::<Vec<_>>(),
+        );
+
+        let (arg_steps, rest) = core_steps.split_at(arg.len());
+        strides.push(arg_steps);
+        core_steps = rest;
+    }
+
+    let mut ptrs = slice::from_raw_parts(args, nargs).to_vec();
+
+    abort_on_panic(|| {
+        for _ in 0..len {
+            kernel.f.call(&ptrs, &shapes, &strides);
+
+            for (ptr, step) in ptrs.iter_mut().zip(outer_steps) {
+                *ptr = ptr.offset(*step);
+            }
+        }
+    });
+}
+
+/// Creates a view of the core dimensions in the same manner as [`PyArray::as_view`][crate::PyArray].
+unsafe fn core_view<A, S, D, F>(
+    mut ptr: *mut c_char,
+    shape: &[usize],
+    strides: &[npy_intp],
+    from_shape_ptr: F,
+) -> ArrayBase<S, D>
+where
+    S: RawData,
+    D: Dimension,
+    F: FnOnce(StrideShape<D>, *mut A) -> ArrayBase<S, D>,
+{
+    let itemsize = mem::size_of::<A>() as isize;
+
+    let dim = D::from_dimension(&Dim(shape)).expect(DIMENSIONALITY_MISMATCH_ERR);
+    let mut new_strides = D::zeros(strides.len());
+    let mut inverted_axes = 0_u32;
+
+    for i in 0..strides.len() {
+        assert!(
+            strides[i] % itemsize == 0,
+            "core dimensions must be strided in multiples of the element size"
+        );
+
+        if strides[i] >= 0 {
+            new_strides[i] = (strides[i] / itemsize) as usize;
+        } else {
+            ptr = ptr.offset(strides[i] * (shape[i] as isize - 1));
+
+            new_strides[i] = (-strides[i] / itemsize) as usize;
+            inverted_axes |= 1 << i;
+        }
+    }
+
+    let mut array = from_shape_ptr(dim.strides(new_strides), ptr as *mut A);
+
+    while inverted_axes != 0 {
+        let axis = inverted_axes.trailing_zeros() as usize;
+        inverted_axes &= !(1 << axis);
+
+        array.invert_axis(Axis(axis));
+    }
+
+    array
+}
+
+/// Aborts the process if a loop panics as NumPy may call loops without holding the GIL
+/// and does not check for Python exceptions afterwards, so that a panic cannot be reported.
+fn abort_on_panic<F: FnOnce()>(f: F) {
+    if catch_unwind(AssertUnwindSafe(f)).is_err() {
+        process::abort();
+    }
+}
@@ -0,0 +1,284 @@
+//! Safe interface for NumPy's [N-dimensional arrays][ndarray] whose element type is only known at runtime
+//!
+//! [ndarray]: https://numpy.org/doc/stable/reference/arrays.ndarray.html
+
+use std::{os::raw::c_int, slice};
+
+use pyo3::{
+    ffi, pyobject_native_type_extract, pyobject_native_type_named, AsPyPointer, PyAny,
+    PyNativeType, PyTypeInfo, Python,
+};
+
+use crate::array::{PyArray, PyArrayDyn};
+use crate::cold;
+use crate::dtype::{Element, PyArrayDescr};
+use crate::npyffi;
+
+/// A safe, untyped wrapper for NumPy's [`ndarray`][ndarray] class.
+///
+/// Unlike [`PyArray<T,D>`][crate::PyArray], this type does not constrain either element type `T` nor the dimensionality `D`.
+/// This can be useful to inspect function arguments, e.g. to dispatch to typed implementations
+/// based on the [`dtype`][Self::dtype] using the [`match_dtype`][crate::match_dtype] macro.
+///
+/// # Example
+///
+/// ```
+/// use numpy::{match_dtype, PyUntypedArray};
+/// use pyo3::{types::IntoPyDict, Python};
+///
+/// Python::with_gil(|py| {
+///     let locals = [("np", numpy::get_array_module(py).unwrap())].into_py_dict(py);
+///
+///     let array: &PyUntypedArray = py
+///         .eval("np.array([1, 2, 3], dtype=np.int16)", None, Some(locals))
+///         .unwrap()
+///         .downcast()
+///         .unwrap();
+///
+///     assert_eq!(array.shape(), [3]);
+///
+///     let sum = match_dtype!(array, [i16, i32, i64], array => array.readonly().as_array().sum() as f64);
+///     assert_eq!(sum, Some(6.0));
+/// });
+/// ```
+///
+/// [ndarray]: https://numpy.org/doc/stable/reference/arrays.ndarray.html
+#[repr(transparent)]
+pub struct PyUntypedArray(PyAny);
+
+pyobject_native_type_named!(PyUntypedArray);
+
+unsafe impl PyTypeInfo for PyUntypedArray {
+    type AsRefTarget = Self;
+
+    const NAME: &'static str = "PyUntypedArray";
+    const MODULE: Option<&'static str> = Some("numpy");
+
+    #[inline]
+    fn type_object_raw(py: Python) -> *mut ffi::PyTypeObject {
+        unsafe { npyffi::PY_ARRAY_API.get_type_object(py, npyffi::NpyTypes::PyArray_Type) }
+    }
+
+    fn is_type_of(ob: &PyAny) -> bool {
+        unsafe { npyffi::PyArray_Check(ob.py(), ob.as_ptr()) != 0 }
+    }
+}
+
+pyobject_native_type_extract!(PyUntypedArray);
+
+impl<'a, T, D> From<&'a PyArray<T, D>> for &'a PyUntypedArray {
+    fn from(array: &'a PyArray<T, D>) -> Self {
+        array.as_untyped()
+    }
+}
+
+impl PyUntypedArray {
+    /// Returns a raw pointer to the underlying [`PyArrayObject`][npyffi::PyArrayObject].
+    #[inline]
+    pub fn as_array_ptr(&self) -> *mut npyffi::PyArrayObject {
+        self.as_ptr() as _
+    }
+
+    /// Returns the `dtype` of the array.
+    ///
+    /// See also [`ndarray.dtype`][ndarray-dtype] and [`PyArray_DTYPE`][PyArray_DTYPE].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use numpy::{dtype, PyArray};
+    /// use pyo3::Python;
+    ///
+    /// Python::with_gil(|py| {
+    ///    let array = PyArray::from_vec(py, vec![1_i32, 2, 3]).as_untyped();
+    ///
+    ///    assert!(array.dtype().is_equiv_to(dtype::<i32>(py)));
+    /// });
+    /// ```
+    ///
+    /// [ndarray-dtype]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.dtype.html
+    /// [PyArray_DTYPE]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_DTYPE
+    pub fn dtype(&self) -> &PyArrayDescr {
+        unsafe {
+            let descr_ptr = (*self.as_array_ptr()).descr;
+            self.py().from_borrowed_ptr(descr_ptr as _)
+        }
+    }
+
+    #[doc(hidden)]
+    pub fn __match_dtype(&self) -> DtypeMatcher<'_> {
+        DtypeMatcher {
+            array: self,
+            dtype: self.dtype(),
+        }
+    }
+
+    #[inline(always)]
+    pub(crate) fn check_flags(&self, flags: c_int) -> bool {
+        unsafe { (*self.as_array_ptr()).flags & flags != 0 }
+    }
+
+    /// Returns `true` if the internal data of the array is contiguous,
+    /// indepedently of whether C-style/row-major or Fortran-style/column-major.
+    pub fn is_contiguous(&self) -> bool {
+        self.check_flags(npyffi::NPY_ARRAY_C_CONTIGUOUS | npyffi::NPY_ARRAY_F_CONTIGUOUS)
+    }
+
+    /// Returns `true` if the internal data of the array is Fortran-style/column-major contiguous.
+    pub fn is_fortran_contiguous(&self) -> bool {
+        self.check_flags(npyffi::NPY_ARRAY_F_CONTIGUOUS)
+    }
+
+    /// Returns `true` if the internal data of the array is C-style/row-major contiguous.
+    pub fn is_c_contiguous(&self) -> bool {
+        self.check_flags(npyffi::NPY_ARRAY_C_CONTIGUOUS)
+    }
+
+    /// Returns the number of dimensions of the array.
+    ///
+    /// See also [`ndarray.ndim`][ndarray-ndim] and [`PyArray_NDIM`][PyArray_NDIM].
+    ///
+    /// [ndarray-ndim]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.ndim.html
+    /// [PyArray_NDIM]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_NDIM
+    #[inline]
+    pub fn ndim(&self) -> usize {
+        unsafe { (*self.as_array_ptr()).nd as usize }
+    }
+
+    /// Returns a slice indicating how many bytes to advance when iterating along each axis.
+    ///
+    /// See also [`ndarray.strides`][ndarray-strides] and [`PyArray_STRIDES`][PyArray_STRIDES].
+    ///
+    /// [ndarray-strides]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.strides.html
+    /// [PyArray_STRIDES]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_STRIDES
+    #[inline]
+    pub fn strides(&self) -> &[isize] {
+        let n = self.ndim();
+        if n == 0 {
+            cold();
+            return &[];
+        }
+        let ptr = self.as_array_ptr();
+        unsafe {
+            let p = (*ptr).strides;
+            slice::from_raw_parts(p, n)
+        }
+    }
+
+    /// Returns a slice which contains dimmensions of the array.
+    ///
+    /// See also [`ndarray.shape`][ndarray-shape] and [`PyArray_DIMS`][PyArray_DIMS].
+    ///
+    /// [ndarray-shape]: https://numpy.org/doc/stable/reference/generated/numpy.ndarray.shape.html
+    /// [PyArray_DIMS]: https://numpy.org/doc/stable/reference/c-api/array.html#c.PyArray_DIMS
+    #[inline]
+    pub fn shape(&self) -> &[usize] {
+        let n = self.ndim();
+        if n == 0 {
+            cold();
+            return &[];
+        }
+        let ptr = self.as_array_ptr();
+        unsafe {
+            let p = (*ptr).dimensions as *mut usize;
+            slice::from_raw_parts(p, n)
+        }
+    }
+
+    /// Calculates the total number of elements in the array.
+    pub fn len(&self) -> usize {
+        self.shape().iter().product()
+    }
+
+    /// Returns `true` if the there are no elements in the array.
+    pub fn is_empty(&self) -> bool {
+        self.shape().contains(&0)
+    }
+}
+
+/// Dispatches on the element type of a [`PyUntypedArray`] by converting it into
+/// a [`PyArrayDyn<T>`][crate::PyArrayDyn] of the matching built-in element type.
+///
+/// The body is instantiated once per candidate type with `$array` bound to the typed array
+/// and the macro evaluates to `Some(body)` for the first candidate type matching the array's
+/// [`dtype`][PyUntypedArray::dtype] or `None` if none of them does.
+///
+/// If no list of candidate types is given, all built-in numeric types are tried, i.e.
+/// `i8`, `i16`, `i32`, `i64`, `u8`, `u16`, `u32`, `u64`, `f32`, `f64`, `Complex32` and `Complex64`.
+///
+/// # Example
+///
+/// ```
+/// use numpy::{match_dtype, PyArray, PyUntypedArray};
+/// use pyo3::Python;
+///
+/// fn describe(array: &PyUntypedArray) -> String {
+///     match_dtype!(array, [bool, f64], typed => format!("{:?}", typed.to_vec().unwrap()))
+///         .unwrap_or_else(|| format!("unsupported type {}", array.dtype()))
+/// }
+///
+/// Python::with_gil(|py| {
+///     assert_eq!(describe(PyArray::from_vec(py, vec![true, false]).as_untyped()), "[true, false]");
+///     assert_eq!(describe(PyArray::from_vec(py, vec![1.5_f64]).as_untyped()), "[1.5]");
+///     assert_eq!(describe(PyArray::from_vec(py, vec![1_u8]).as_untyped()), "unsupported type uint8");
+/// });
+/// ```
+#[macro_export]
+macro_rules! match_dtype {
+    (@chain $untyped:ident, [], $array:ident => $body:expr) => {
+        ::std::option::Option::None
+    };
+    (@chain $untyped:ident, [$ty:ty $(, $rest:ty)*], $array:ident => $body:expr) => {
+        if let ::std::option::Option::Some($array) = $untyped.downcast::<$ty>() {
+            ::std::option::Option::Some($body)
+        } else {
+            $crate::match_dtype!(@chain $untyped, [$($rest),*], $array => $body)
+        }
+    };
+    ($untyped:expr, $array:ident => $body:expr) => {
+        $crate::match_dtype!(
+            $untyped,
+            [
+                i8,
+                i16,
+                i32,
+                i64,
+                u8,
+                u16,
+                u32,
+                u64,
+                f32,
+                f64,
+                $crate::Complex32,
+                $crate::Complex64
+            ],
+            $array => $body
+        )
+    };
+    ($untyped:expr, [$($ty:ty),+ $(,)?], $array:ident => $body:expr) => {{
+        let untyped: &$crate::PyUntypedArray = $untyped;
+        let matcher = untyped.__match_dtype();
+
+        $crate::match_dtype!(@chain matcher, [$($ty),+], $array => $body)
+    }};
+}
+
+/// Reads the `dtype` of an array once so that [`match_dtype`][crate::match_dtype]
+/// does not have to repeat the full type check for each candidate type.
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct DtypeMatcher<'py> {
+    array: &'py PyUntypedArray,
+    dtype: &'py PyArrayDescr,
+}
+
+impl<'py> DtypeMatcher<'py> {
+    pub fn downcast<T: Element>(&self) -> Option<&'py PyArrayDyn<T>> {
+        if self.dtype.is_equiv_to(T::get_dtype(self.array.py())) {
+            // SAFETY: The element types match and `IxDyn` accepts any dimensionality.
+            Some(unsafe { &*(self.array as *const PyUntypedArray as *const PyArrayDyn<T>) })
+        } else {
+            None
+        }
+    }
+}
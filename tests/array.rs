@@ -2,10 +2,11 @@ use std::mem::size_of;
 
 #[cfg(feature = "half")]
 use half::f16;
-use ndarray::{array, s, Array1, Dim};
+use ndarray::{array, s, Array1, Axis, Dim};
 use numpy::{
-    dtype, get_array_module, pyarray, PyArray, PyArray1, PyArray2, PyArrayDescr, PyArrayDyn,
-    ToPyArray,
+    dtype, get_array_module, match_dtype, npyffi::NPY_ORDER, pyarray, Casting, Complex32,
+    Complex64, PyArray, PyArray1, PyArray2, PyArrayDescr, PyArrayDyn, PyFixedString,
+    PyFixedUnicode, PyUntypedArray, SearchSide, SortKind, ToPyArray,
 };
 use pyo3::{
     py_run, pyclass, pymethods,
@@ -351,6 +352,112 @@ fn array_cast() {
     });
 }
 
+#[test]
+fn array_cast_with_casting() {
+    Python::with_gil(|py| {
+        let arr_f64 = pyarray![py, [1.5, 2.5, 3.5], [1.5, 2.5, 3.5]];
+
+        assert!(arr_f64.can_cast_to::<f64>(Casting::No));
+        assert!(!arr_f64.can_cast_to::<f32>(Casting::Safe));
+        assert!(arr_f64.can_cast_to::<f32>(Casting::SameKind));
+        assert!(!arr_f64.can_cast_to::<u8>(Casting::SameKind));
+
+        let err = arr_f64
+            .cast_with::<u8>(Casting::Safe, NPY_ORDER::NPY_KEEPORDER)
+            .unwrap_err();
+        assert!(err.to_string().contains("according to the rule 'safe'"));
+
+        let arr_u8 = arr_f64
+            .cast_with::<u8>(Casting::Unsafe, NPY_ORDER::NPY_KEEPORDER)
+            .unwrap();
+        assert_eq!(arr_u8.readonly().as_array(), array![[1, 2, 3], [1, 2, 3]]);
+
+        let arr_f32 = arr_f64
+            .cast_with::<f32>(Casting::SameKind, NPY_ORDER::NPY_FORTRANORDER)
+            .unwrap();
+        assert!(arr_f32.is_fortran_contiguous());
+        assert_eq!(
+            arr_f32.readonly().as_array(),
+            array![[1.5, 2.5, 3.5], [1.5, 2.5, 3.5]]
+        );
+
+        let arr_f64_f = arr_f64
+            .reshape_with_order([2, 3], NPY_ORDER::NPY_FORTRANORDER)
+            .unwrap();
+        let arr_f64_k = arr_f64_f
+            .cast_with::<f64>(Casting::No, NPY_ORDER::NPY_KEEPORDER)
+            .unwrap();
+        assert!(arr_f64_k.is_fortran_contiguous());
+    });
+}
+
+#[cfg(feature = "slice-view")]
+#[test]
+fn slice_view_shares_data() {
+    Python::with_gil(|py| {
+        let pyarray = PyArray::from_owned_array(py, Array1::range(0.0, 10.0, 1.0))
+            .reshape([2, 5])
+            .unwrap();
+
+        let view = pyarray.slice_view(s![.., 1..4]);
+        assert_eq!(view.shape(), [2, 3]);
+        assert_eq!(view.strides(), [40, 8]);
+        assert_eq!(
+            view.readonly().as_array(),
+            array![[1.0, 2.0, 3.0], [6.0, 7.0, 8.0]]
+        );
+
+        py_run!(
+            py,
+            pyarray view,
+            "view[1, 2] = -1.0\nassert pyarray[1, 3] == -1.0"
+        );
+    });
+}
+
+#[cfg(feature = "slice-view")]
+#[test]
+fn slice_view_with_negative_steps() {
+    Python::with_gil(|py| {
+        let pyarray = PyArray::arange(py, 0, 12, 1).reshape([3, 4]).unwrap();
+
+        let view = pyarray.slice_view(s![..;-1, 1..;-2]);
+        assert_eq!(view.shape(), [3, 2]);
+        assert_eq!(view.strides(), [-16, -8]);
+        assert_eq!(view.readonly().as_array(), array![[11, 9], [7, 5], [3, 1]]);
+
+        let view = view.slice_view(s![1, ..]);
+        assert_eq!(view.readonly().as_array(), array![7, 5]);
+        py_run!(py, view, "assert view.tolist() == [7, 5]");
+    });
+}
+
+#[cfg(feature = "slice-view")]
+#[test]
+fn slice_view_with_new_axis() {
+    use ndarray::NewAxis;
+
+    Python::with_gil(|py| {
+        let pyarray = PyArray::arange(py, 0, 4, 1);
+
+        let view = pyarray.slice_view(s![NewAxis, 1..3]);
+        assert_eq!(view.shape(), [1, 2]);
+        assert_eq!(view.readonly().as_array(), array![[1, 2]]);
+    });
+}
+
+#[cfg(feature = "slice-view")]
+#[test]
+fn slice_view_keeps_readonly_flag() {
+    Python::with_gil(|py| {
+        let pyarray = PyArray::arange(py, 0, 4, 1);
+        py_run!(py, pyarray, "pyarray.flags.writeable = False");
+
+        let view = pyarray.slice_view(s![1..]);
+        assert!(view.try_readwrite().is_err());
+    });
+}
+
 #[test]
 fn handle_negative_strides() {
     Python::with_gil(|py| {
@@ -489,6 +596,24 @@ fn copy_to_works() {
     });
 }
 
+#[test]
+fn copy_to_with_casting() {
+    Python::with_gil(|py| {
+        let arr1 = PyArray::from_slice(py, &[2_i64, 3, 300]);
+        let arr2 = PyArray::<u8, _>::zeros(py, [3], false);
+
+        assert!(arr1.copy_to_with(arr2, Casting::SameKind).is_err());
+        assert_eq!(arr2.readonly().as_slice().unwrap(), &[0, 0, 0]);
+
+        arr1.copy_to_with(arr2, Casting::Unsafe).unwrap();
+        assert_eq!(arr2.readonly().as_slice().unwrap(), &[2, 3, 44]);
+
+        let arr3 = PyArray::<f64, _>::zeros(py, [3], false);
+        arr1.copy_to_with(arr3, Casting::Safe).unwrap();
+        assert_eq!(arr3.readonly().as_slice().unwrap(), &[2.0, 3.0, 300.0]);
+    });
+}
+
 #[test]
 fn get_works() {
     Python::with_gil(|py| {
@@ -545,3 +670,476 @@ fn half_works() {
         );
     });
 }
+
+#[test]
+fn fixed_width_strings_work() {
+    Python::with_gil(|py| {
+        let array = py
+            .eval(
+                "np.array([b'foo', b'barbaz'], dtype='S8')",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray1<PyFixedString<8>>>()
+            .unwrap();
+
+        {
+            let array = array.readonly();
+            assert_eq!(array.get(0).unwrap().as_bytes(), b"foo");
+            assert_eq!(array.get(1).unwrap().as_bytes(), b"barbaz");
+        }
+
+        let array = py
+            .eval(
+                "np.array(['foo', 'bär'], dtype='<U4')",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray1<PyFixedUnicode<4>>>()
+            .unwrap();
+
+        {
+            let array = array.readonly();
+            assert_eq!(array.get(0).unwrap().to_string(), "foo");
+            assert_eq!(array.get(1).unwrap().to_string(), "bär");
+        }
+
+        let array = PyArray1::from_vec(
+            py,
+            vec![
+                PyFixedString::from(*b"ab\0\0"),
+                PyFixedString::from(*b"abcd"),
+            ],
+        );
+        py_run!(py, array, "assert array.tolist() == [b'ab', b'abcd']");
+    });
+}
+
+#[test]
+fn untyped_array_metadata() {
+    Python::with_gil(|py| {
+        let array = py
+            .eval(
+                "np.zeros((2, 3), dtype='int16', order='F')",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyUntypedArray>()
+            .unwrap();
+
+        assert_eq!(array.ndim(), 2);
+        assert_eq!(array.shape(), [2, 3]);
+        assert_eq!(array.strides(), [2, 4]);
+        assert_eq!(array.len(), 6);
+        assert!(array.is_fortran_contiguous());
+        assert!(!array.is_c_contiguous());
+        assert!(array.dtype().is_equiv_to(dtype::<i16>(py)));
+
+        let typed = PyArray::from_vec(py, vec![1_u8, 2]);
+        let untyped: &PyUntypedArray = typed.into();
+        assert!(untyped.is(typed));
+
+        assert!(py
+            .eval("[1, 2]", None, None)
+            .unwrap()
+            .downcast::<PyUntypedArray>()
+            .is_err());
+    });
+}
+
+#[test]
+fn untyped_array_dispatch() {
+    #[allow(clippy::unnecessary_cast)]
+    fn sum(array: &PyUntypedArray) -> Option<f64> {
+        match_dtype!(array, [i8, i16, i32, i64, u8, u16, u32, u64, f32, f64], array => {
+            let array = array.readonly();
+            array.as_array().iter().map(|&elem| elem as f64).sum()
+        })
+    }
+
+    Python::with_gil(|py| {
+        for dtype in &["int8", "uint16", "int32", "uint64", "float32", "float64"] {
+            let array = py
+                .eval(
+                    &format!("np.arange(4, dtype='{}')", dtype),
+                    None,
+                    Some(get_np_locals(py)),
+                )
+                .unwrap()
+                .downcast::<PyUntypedArray>()
+                .unwrap();
+
+            assert_eq!(sum(array), Some(6.0));
+        }
+
+        let array = PyArray::from_vec(py, vec![true, false]);
+        assert_eq!(sum(array.as_untyped()), None);
+        assert_eq!(match_dtype!(array.as_untyped(), array => array.len()), None);
+
+        let array = PyArray::from_vec(py, vec![Complex64::new(1.0, 2.0)]);
+        assert_eq!(
+            match_dtype!(array.as_untyped(), array => array.len()),
+            Some(1)
+        );
+
+        let array = PyArray::from_vec(py, vec![true, false, true]);
+        let count = match_dtype!(array.as_untyped(), [bool], array => {
+            array.readonly().as_array().iter().filter(|&&elem| elem).count()
+        });
+        assert_eq!(count, Some(2));
+    });
+}
+
+#[test]
+fn subarray_view_of_trailing_axis() {
+    Python::with_gil(|py| {
+        let base = py
+            .eval(
+                "np.arange(12, dtype='float32').reshape(4, 3)",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap();
+
+        assert!(base.extract::<&PyArray1<[f32; 3]>>().is_err());
+
+        let array = base.downcast::<PyArray2<f32>>().unwrap();
+
+        {
+            let array = array.readonly();
+            let points = array.as_array_of::<3>().unwrap();
+            assert_eq!(points.shape(), [4]);
+            assert_eq!(points[1], [3.0, 4.0, 5.0]);
+
+            assert!(array.as_array_of::<4>().is_err());
+        }
+
+        array.readwrite().as_array_mut_of::<3>().unwrap()[2][0] = -1.0;
+        py_run!(py, base, "assert base[2, 0] == -1.0");
+
+        let array = base.downcast::<PyArrayDyn<f32>>().unwrap().readonly();
+        assert_eq!(array.as_array_of::<3>().unwrap().shape(), [4]);
+    });
+}
+
+#[test]
+fn subarray_view_requires_contiguous_trailing_axis() {
+    Python::with_gil(|py| {
+        let array = py
+            .eval(
+                "np.arange(12, dtype='float32').reshape(3, 4).T",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray2<f32>>()
+            .unwrap();
+
+        assert!(array.readonly().as_array_of::<3>().is_err());
+
+        let array = py
+            .eval(
+                "np.arange(24, dtype='float32').reshape(4, 6)[:, ::2]",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray2<f32>>()
+            .unwrap();
+
+        assert!(array.readonly().as_array_of::<3>().is_err());
+
+        let array = py
+            .eval(
+                "np.arange(20, dtype='float32').reshape(4, 5)[:, :3]",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray2<f32>>()
+            .unwrap();
+
+        assert!(array.readonly().as_array_of::<3>().is_err());
+
+        let array = py
+            .eval(
+                "np.arange(24, dtype='float32').reshape(4, 6)[::-1, 3:]",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray2<f32>>()
+            .unwrap();
+
+        let array = array.readonly();
+        let points = array.as_array_of::<3>().unwrap();
+        assert_eq!(points[0], [21.0, 22.0, 23.0]);
+        assert_eq!(points[3], [3.0, 4.0, 5.0]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Arrays of sub-array element types like `[T; N]` are not supported")]
+fn subarray_arrays_cannot_be_created() {
+    Python::with_gil(|py| {
+        let _ = PyArray1::<[f64; 2]>::zeros(py, 3, false);
+    });
+}
+
+#[test]
+#[should_panic(expected = "Arrays of sub-array element types like `[T; N]` are not supported")]
+fn subarray_arrays_cannot_be_created_from_vec() {
+    Python::with_gil(|py| {
+        let _ = PyArray1::from_vec(py, vec![[1_i32, 2], [3, 4]]);
+    });
+}
+
+#[test]
+fn transpose_and_swap_axes_are_views() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[1, 2, 3], [4, 5, 6]]);
+
+        let transposed = array.transpose().unwrap();
+        assert_eq!(transposed.shape(), [3, 2]);
+        assert_eq!(transposed.data(), array.data());
+        assert_eq!(
+            transposed.readonly().as_array(),
+            array![[1, 4], [2, 5], [3, 6]]
+        );
+
+        let swapped = array.swap_axes(Axis(1), Axis(0)).unwrap();
+        assert_eq!(swapped.shape(), [3, 2]);
+        assert_eq!(swapped.data(), array.data());
+
+        assert!(array.swap_axes(Axis(0), Axis(2)).is_err());
+    });
+}
+
+#[test]
+fn permute_axes_checks_permutation() {
+    Python::with_gil(|py| {
+        let array = PyArray::<i32, _>::zeros(py, [2, 3, 4], false);
+
+        let permuted = array.permute_axes([2, 0, 1]).unwrap();
+        assert_eq!(permuted.shape(), [4, 2, 3]);
+        assert_eq!(permuted.strides(), [4, 48, 16]);
+
+        assert!(array.permute_axes([0, 1, 1]).is_err());
+        assert!(array.permute_axes([0, 1, 3]).is_err());
+    });
+}
+
+#[test]
+fn squeeze_removes_unit_axes() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, [1, 2, 1, 3], false);
+
+        let squeezed = array.squeeze().unwrap();
+        assert_eq!(squeezed.shape(), [2, 3]);
+        assert_eq!(squeezed.data(), array.data());
+    });
+}
+
+#[test]
+fn expand_dims_inserts_unit_axis() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[1, 2, 3], [4, 5, 6]]);
+
+        let expanded: &PyArray<i32, _> = array.expand_dims(Axis(0));
+        assert_eq!(expanded.shape(), [1, 2, 3]);
+        assert_eq!(expanded.data(), array.data());
+
+        let expanded = array.expand_dims(Axis(2));
+        assert_eq!(expanded.shape(), [2, 3, 1]);
+        assert_eq!(
+            expanded.readonly().as_array(),
+            array![[[1], [2], [3]], [[4], [5], [6]]]
+        );
+
+        py_run!(py, array expanded, "expanded[1, 2, 0] = 42; assert array[1, 2] == 42");
+    });
+}
+
+#[test]
+#[should_panic(expected = "axis 3 is out of bounds for array of dimension 2")]
+fn expand_dims_out_of_bounds() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, [2, 3], false);
+
+        array.expand_dims(Axis(3));
+    });
+}
+
+#[test]
+fn ravel_returns_view_if_possible() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[1, 2], [3, 4]]);
+
+        let raveled = array.ravel(NPY_ORDER::NPY_CORDER).unwrap();
+        assert_eq!(raveled.data(), array.data());
+        assert_eq!(raveled.readonly().as_slice().unwrap(), [1, 2, 3, 4]);
+
+        let raveled = array.ravel(NPY_ORDER::NPY_FORTRANORDER).unwrap();
+        assert_ne!(raveled.data(), array.data());
+        assert_eq!(raveled.readonly().as_slice().unwrap(), [1, 3, 2, 4]);
+
+        let flattened = array.flatten(NPY_ORDER::NPY_CORDER).unwrap();
+        assert_ne!(flattened.data(), array.data());
+        assert_eq!(flattened.readonly().as_slice().unwrap(), [1, 2, 3, 4]);
+    });
+}
+
+#[test]
+fn sum_and_prod_promote_integers() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[100_i8, 100], [100, 100]]);
+
+        let sum: i64 = array.sum().unwrap();
+        assert_eq!(sum, 400);
+
+        let prod: i64 = array.prod().unwrap();
+        assert_eq!(prod, 100_000_000);
+
+        let sums: &PyArray1<i64> = array.sum_axis(Axis(0)).unwrap();
+        assert_eq!(sums.readonly().as_slice().unwrap(), [200, 200]);
+
+        let array = PyArray::from_vec(py, vec![true, false, true]);
+        assert_eq!(array.sum().unwrap(), 2_i64);
+
+        let array = PyArray::from_vec(py, vec![0.5_f32, 1.5]);
+        assert_eq!(array.sum().unwrap(), 2.0_f32);
+    });
+}
+
+#[test]
+fn mean_and_std_along_axis() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[1_i32, 2, 3], [4, 6, 8]]);
+
+        assert_eq!(array.mean().unwrap(), 4.0);
+
+        let means: &PyArray1<f64> = array.mean_axis(Axis(1)).unwrap();
+        assert_eq!(means.readonly().as_slice().unwrap(), [2.0, 6.0]);
+
+        let stds: &PyArray1<f64> = array.std_axis(Axis(0)).unwrap();
+        assert_eq!(stds.readonly().as_slice().unwrap(), [1.5, 2.0, 2.5]);
+
+        let array = PyArray::from_vec(
+            py,
+            vec![Complex64::new(1.0, 1.0), Complex64::new(-1.0, -1.0)],
+        );
+        assert_eq!(array.mean().unwrap(), Complex64::new(0.0, 0.0));
+
+        let std: f64 = array.std().unwrap();
+        assert!((std - 2.0_f64.sqrt()).abs() < 1e-12);
+
+        let array = PyArray::from_owned_array(
+            py,
+            array![
+                [Complex32::new(3.0, 0.0), Complex32::new(0.0, 1.0)],
+                [Complex32::new(-3.0, 0.0), Complex32::new(0.0, -1.0)]
+            ],
+        );
+
+        let stds: &PyArray1<f32> = array.std_axis(Axis(0)).unwrap();
+        assert_eq!(stds.readonly().as_slice().unwrap(), [3.0, 1.0]);
+    });
+}
+
+#[test]
+fn min_max_and_argmax() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[3, 7, 1], [9, 2, 5]]);
+
+        assert_eq!(array.min().unwrap(), 1);
+        assert_eq!(array.max().unwrap(), 9);
+        assert_eq!(array.argmax().unwrap(), 3);
+
+        let minima = array.min_axis(Axis(1)).unwrap();
+        assert_eq!(minima.readonly().as_slice().unwrap(), [1, 2]);
+
+        let maxima = array.max_axis(Axis(0)).unwrap();
+        assert_eq!(maxima.readonly().as_slice().unwrap(), [9, 7, 5]);
+
+        let indices = array.argmax_axis(Axis(1)).unwrap();
+        assert_eq!(indices.readonly().as_slice().unwrap(), [1, 0]);
+
+        let empty = PyArray::<i32, _>::zeros(py, [0, 3], false);
+        assert!(empty.max().is_err());
+        assert!(empty.argmax().is_err());
+    });
+}
+
+#[test]
+fn cumsum_flattens_or_keeps_shape() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[1_u16, 2, 3], [4, 5, 6]]);
+
+        let sums: &PyArray1<u64> = array.cumsum().unwrap();
+        assert_eq!(sums.readonly().as_slice().unwrap(), [1, 3, 6, 10, 15, 21]);
+
+        let sums: &PyArray2<u64> = array.cumsum_axis(Axis(1)).unwrap();
+        assert_eq!(sums.readonly().as_array(), array![[1, 3, 6], [4, 9, 15]]);
+    });
+}
+
+#[test]
+#[should_panic(expected = "axis 2 is out of bounds for array of dimension 2")]
+fn reduction_along_invalid_axis() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, [2, 3], false);
+
+        let _ = array.sum_axis(Axis(2));
+    });
+}
+
+#[test]
+fn argsort_and_argpartition() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[30, 10, 20], [5, 15, 10]]);
+
+        let indices: &PyArray2<isize> = array.argsort(Axis(0), SortKind::QuickSort).unwrap();
+        assert_eq!(indices.readonly().as_array(), array![[1, 0, 1], [0, 1, 0]]);
+
+        let indices = array.argsort(Axis(1), SortKind::MergeSort).unwrap();
+        assert_eq!(indices.readonly().as_array(), array![[1, 2, 0], [0, 2, 1]]);
+
+        assert!(array.argsort(Axis(2), SortKind::QuickSort).is_err());
+
+        let indices = array.argpartition(&[0, 2], Axis(1)).unwrap();
+        assert_eq!(indices.readonly().as_array().column(0), array![1, 0]);
+        assert_eq!(indices.readonly().as_array().column(2), array![0, 1]);
+
+        assert!(array.argpartition(&[3], Axis(1)).is_err());
+    });
+}
+
+#[test]
+fn lexsort_uses_last_key_first() {
+    Python::with_gil(|py| {
+        let minor = PyArray::from_slice(py, &[2.0, 1.0, 0.0, 3.0]);
+        let major = PyArray::from_slice(py, &[1.0, 1.0, 0.0, 0.0]);
+
+        let indices = PyArray::lexsort(py, &[minor, major], Axis(0)).unwrap();
+        assert_eq!(indices.readonly().as_slice().unwrap(), [2, 3, 1, 0]);
+
+        assert!(PyArray1::<f64>::lexsort(py, &[], Axis(0)).is_err());
+    });
+}
+
+#[test]
+fn searchsorted_keeps_shape_of_values() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_slice(py, &[1, 3, 5, 7]);
+        let values = PyArray::from_owned_array(py, array![[0, 3], [6, 8]]);
+
+        let indices = array.searchsorted(values, SearchSide::Left).unwrap();
+        assert_eq!(indices.readonly().as_array(), array![[0, 1], [3, 4]]);
+
+        let indices = array.searchsorted(values, SearchSide::Right).unwrap();
+        assert_eq!(indices.readonly().as_array(), array![[0, 2], [3, 4]]);
+    });
+}
@@ -0,0 +1,255 @@
+use ndarray::array;
+use numpy::{
+    get_array_module, PyArray, PyArray1, PyArray2, PyArrayLike1, PyArrayLike2, PyArrayLikeDyn,
+    PyWritebackArray1, PyWritebackArray2,
+};
+use pyo3::{
+    py_run,
+    types::{IntoPyDict, PyDict},
+    Python, ToPyObject,
+};
+
+fn get_np_locals(py: Python) -> &PyDict {
+    [("np", get_array_module(py).unwrap())].into_py_dict(py)
+}
+
+#[test]
+fn extract_reference() {
+    Python::with_gil(|py| {
+        let py_array = py
+            .eval(
+                "np.array([[1.0, 2.0], [3.0, 4.0]], dtype='float64')",
+                Some(get_np_locals(py)),
+                None,
+            )
+            .unwrap();
+        let extracted_array = py_array.extract::<PyArrayLike2<f64>>().unwrap();
+
+        assert!(extracted_array.is(py_array));
+        assert_eq!(array![[1.0, 2.0], [3.0, 4.0]], extracted_array.as_array());
+    });
+}
+
+#[test]
+fn convert_array_on_extract() {
+    Python::with_gil(|py| {
+        let py_array = py
+            .eval(
+                "np.array([[1, 2], [3, 4]], dtype='int32')",
+                Some(get_np_locals(py)),
+                None,
+            )
+            .unwrap();
+        let extracted_array = py_array.extract::<PyArrayLike2<f64>>().unwrap();
+
+        assert!(!extracted_array.is(py_array));
+        assert_eq!(array![[1.0, 2.0], [3.0, 4.0]], extracted_array.as_array());
+    });
+}
+
+#[test]
+fn convert_list_on_extract() {
+    Python::with_gil(|py| {
+        let py_list = py.eval("[[1.0, 2.0], [3.0, 4.0]]", None, None).unwrap();
+        let extracted_array = py_list.extract::<PyArrayLike2<f64>>().unwrap();
+
+        assert_eq!(array![[1.0, 2.0], [3.0, 4.0]], extracted_array.as_array());
+
+        let py_tuple = py.eval("(1, 2, 3)", None, None).unwrap();
+        let extracted_array = py_tuple.extract::<PyArrayLike1<i64>>().unwrap();
+
+        assert_eq!(extracted_array.as_slice().unwrap(), [1, 2, 3]);
+    });
+}
+
+#[test]
+fn convert_scalar_on_extract() {
+    Python::with_gil(|py| {
+        let py_scalar = py.eval("42.0", None, None).unwrap();
+        let extracted_array = py_scalar.extract::<PyArrayLikeDyn<f32>>().unwrap();
+
+        assert_eq!(extracted_array.ndim(), 0);
+        assert_eq!(extracted_array.as_array().into_iter().next(), Some(&42.0));
+    });
+}
+
+#[test]
+fn unsafe_cast_fails_to_extract() {
+    Python::with_gil(|py| {
+        let py_array = py
+            .eval(
+                "np.array([1.5, 2.5], dtype='float64')",
+                Some(get_np_locals(py)),
+                None,
+            )
+            .unwrap();
+
+        assert!(py_array.extract::<PyArrayLike1<i32>>().is_err());
+    });
+}
+
+#[test]
+fn dimensionality_mismatch_fails_to_extract() {
+    Python::with_gil(|py| {
+        let py_list = py.eval("[[1.0, 2.0], [3.0, 4.0]]", None, None).unwrap();
+
+        assert!(py_list.extract::<PyArrayLike1<f64>>().is_err());
+    });
+}
+
+#[test]
+fn extract_keeps_borrow_checking() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_vec(py, vec![1.0_f64, 2.0, 3.0]);
+
+        let extracted_array = array.extract::<PyArrayLike1<f64>>().unwrap();
+        assert!(array.try_readwrite().is_err());
+
+        drop(extracted_array);
+        assert!(array.try_readwrite().is_ok());
+    });
+}
+
+#[test]
+fn writeback_extract_reference() {
+    Python::with_gil(|py| {
+        let py_array = py
+            .eval(
+                "np.zeros(3, dtype='float64')",
+                Some(get_np_locals(py)),
+                None,
+            )
+            .unwrap();
+
+        let mut extracted_array = py_array.extract::<PyWritebackArray1<f64>>().unwrap();
+        assert!(!extracted_array.is_copy());
+        assert!(extracted_array.is(py_array));
+
+        extracted_array.as_slice_mut().unwrap()[1] = 1.0;
+        drop(extracted_array);
+
+        py_run!(py, py_array, "assert py_array.tolist() == [0.0, 1.0, 0.0]");
+    });
+}
+
+#[test]
+fn writeback_converted_array() {
+    Python::with_gil(|py| {
+        let locals = get_np_locals(py);
+        let py_array = py
+            .eval(
+                "np.array([[1, 2, 3], [4, 5, 6]], dtype='float32').T",
+                Some(locals),
+                None,
+            )
+            .unwrap();
+        locals.set_item("py_array", py_array).unwrap();
+
+        let mut extracted_array = py_array.extract::<PyWritebackArray2<f64>>().unwrap();
+        assert!(extracted_array.is_copy());
+        assert!(extracted_array.is_c_contiguous());
+        assert_eq!(
+            extracted_array.as_array(),
+            array![[1.0, 4.0], [2.0, 5.0], [3.0, 6.0]]
+        );
+
+        // The original array is locked while it is being converted.
+        py_run!(py, *locals, "assert not py_array.flags.writeable");
+        let array = py_array.downcast::<PyArray2<f32>>().unwrap();
+        assert!(array.try_readonly().is_err());
+
+        extracted_array.as_array_mut().mapv_inplace(|x| -x);
+        *extracted_array.get_mut([0, 0]).unwrap() = 0.5;
+        drop(extracted_array);
+
+        py_run!(
+            py,
+            *locals,
+            r#"
+assert py_array.flags.writeable
+assert py_array.dtype == np.float32
+assert py_array.T.tolist() == [[0.5, -2.0, -3.0], [-4.0, -5.0, -6.0]]
+"#
+        );
+
+        assert!(array.try_readwrite().is_ok());
+    });
+}
+
+#[test]
+fn writeback_releases_both_borrows() {
+    Python::with_gil(|py| {
+        let py_array = py
+            .eval(
+                "np.arange(4, dtype='float32')[::2]",
+                Some(get_np_locals(py)),
+                None,
+            )
+            .unwrap();
+        let array = py_array.downcast::<PyArray1<f32>>().unwrap();
+
+        let extracted_array = py_array.extract::<PyWritebackArray1<f64>>().unwrap();
+        assert!(extracted_array.is_copy());
+        let copy = extracted_array.to_object(py);
+        drop(extracted_array);
+
+        let copy = copy.extract::<&PyArray1<f64>>(py).unwrap();
+        assert!(copy.try_readwrite().is_ok());
+        assert!(copy.try_readonly().is_ok());
+
+        assert!(array.try_readwrite().is_ok());
+        assert!(array.try_readonly().is_ok());
+    });
+}
+
+#[test]
+fn writeback_requires_same_kind_and_dimensionality() {
+    Python::with_gil(|py| {
+        let locals = get_np_locals(py);
+
+        let py_array = py
+            .eval("np.arange(3, dtype='int64')", Some(locals), None)
+            .unwrap();
+        let err = py_array.extract::<PyWritebackArray1<f64>>().unwrap_err();
+        assert!(err.to_string().contains("cannot cast array data"));
+
+        let py_array = py
+            .eval("np.zeros((2, 2), dtype='float32')", Some(locals), None)
+            .unwrap();
+        assert!(py_array.extract::<PyWritebackArray1<f64>>().is_err());
+
+        let py_list = py.eval("[1.0, 2.0]", None, None).unwrap();
+        assert!(py_list.extract::<PyWritebackArray1<f64>>().is_err());
+    });
+}
+
+#[test]
+fn writeback_respects_borrows_and_flags() {
+    Python::with_gil(|py| {
+        let locals = get_np_locals(py);
+
+        let py_array = py
+            .eval("np.zeros(4, dtype='float32')", Some(locals), None)
+            .unwrap();
+        let array = py_array.downcast::<PyArray1<f32>>().unwrap();
+
+        {
+            let _shared = array.readonly();
+            let err = py_array.extract::<PyWritebackArray1<f64>>().unwrap_err();
+            assert!(err.to_string().contains("already borrowed"));
+        }
+
+        {
+            let _extracted_array = py_array.extract::<PyWritebackArray1<f64>>().unwrap();
+            assert!(array.try_readonly().is_err());
+        }
+
+        assert!(array.try_readwrite().is_ok());
+
+        locals.set_item("py_array", py_array).unwrap();
+        py_run!(py, *locals, "py_array.flags.writeable = False");
+
+        let err = py_array.extract::<PyWritebackArray1<f64>>().unwrap_err();
+        assert!(err.to_string().contains("not writeable"));
+    });
+}
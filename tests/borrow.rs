@@ -1,9 +1,13 @@
 use std::thread::spawn;
 
+use ndarray::{array, Axis};
 use numpy::{
-    npyffi::NPY_ARRAY_WRITEABLE, PyArray, PyArray1, PyArray2, PyReadonlyArray3, PyReadwriteArray3,
+    borrow::{active_borrows, BorrowKind},
+    npyffi::NPY_ARRAY_WRITEABLE,
+    BorrowError, PyArray, PyArray1, PyArray2, PyFrozenArray1, PyReadonlyArray3, PyReadwriteArray3,
+    SortKind,
 };
-use pyo3::{py_run, pyclass, pymethods, types::IntoPyDict, Py, PyAny, Python};
+use pyo3::{py_run, pyclass, pymethods, types::IntoPyDict, Py, PyAny, Python, ToPyObject};
 
 #[test]
 fn distinct_borrows() {
@@ -286,6 +290,60 @@ fn interleaved_views_do_not_conflict() {
     });
 }
 
+#[test]
+fn interleaved_views_with_non_dividing_steps_do_not_conflict() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (10, 10), false);
+        let locals = [("array", array)].into_py_dict(py);
+
+        let view1 = py
+            .eval("array[:,::3]", None, Some(locals))
+            .unwrap()
+            .downcast::<PyArray2<f64>>()
+            .unwrap();
+
+        let view2 = py
+            .eval("array[:,1::3]", None, Some(locals))
+            .unwrap()
+            .downcast::<PyArray2<f64>>()
+            .unwrap();
+
+        let view3 = py
+            .eval("array[:,2::3]", None, Some(locals))
+            .unwrap()
+            .downcast::<PyArray2<f64>>()
+            .unwrap();
+
+        let _exclusive1 = view1.readwrite();
+        let _exclusive2 = view2.readwrite();
+        let _exclusive3 = view3.readwrite();
+    });
+}
+
+#[test]
+#[should_panic(expected = "AlreadyBorrowed")]
+fn misaligned_views_of_different_types_conflict() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 10, false);
+        let locals = [("array", array)].into_py_dict(py);
+
+        let view1 = py
+            .eval("array[::2]", None, Some(locals))
+            .unwrap()
+            .downcast::<PyArray1<f64>>()
+            .unwrap();
+
+        let view2 = py
+            .eval("array.view('u1')[1::16]", None, Some(locals))
+            .unwrap()
+            .downcast::<PyArray1<u8>>()
+            .unwrap();
+
+        let _exclusive1 = view1.readwrite();
+        let _exclusive2 = view2.readwrite();
+    });
+}
+
 #[test]
 fn extract_readonly() {
     Python::with_gil(|py| {
@@ -342,3 +400,316 @@ fn resize_using_exclusive_borrow() {
         assert_eq!(array.as_slice_mut().unwrap(), &[0.0; 5]);
     });
 }
+
+#[cfg(feature = "slice-view")]
+#[test]
+fn slice_view_of_shared_borrow() {
+    use ndarray::s;
+
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (2, 3), false);
+
+        let shared = array.readonly();
+        let view = shared.slice_view(s![.., 1..]);
+        assert_eq!(view.shape(), [2, 2]);
+
+        assert!(array.try_readwrite().is_err());
+
+        drop(shared);
+        assert!(array.try_readwrite().is_err());
+
+        drop(view);
+        assert!(array.try_readwrite().is_ok());
+    });
+}
+
+#[cfg(feature = "slice-view")]
+#[test]
+fn slice_view_of_exclusive_borrow() {
+    use ndarray::s;
+
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 6, false);
+
+        let mut view = array.readwrite().slice_view(s![..;2]);
+        view.as_array_mut().fill(1.0);
+
+        assert!(array.try_readonly().is_err());
+
+        let other_view = array.slice_view(s![1..;2]);
+        assert!(other_view.try_readwrite().is_ok());
+
+        drop(view);
+        assert_eq!(
+            array.readonly().as_slice().unwrap(),
+            &[1.0, 0.0, 1.0, 0.0, 1.0, 0.0]
+        );
+    });
+}
+
+#[test]
+fn sort_requires_exclusive_borrow() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[3, 1, 2], [6, 5, 4]]);
+
+        let shared = array.readonly();
+        assert!(array.try_readwrite().is_err());
+        drop(shared);
+
+        let mut exclusive = array.readwrite();
+        exclusive.sort(Axis(1), SortKind::HeapSort).unwrap();
+        assert_eq!(exclusive.as_array(), array![[1, 2, 3], [4, 5, 6]]);
+
+        exclusive.sort(Axis(0), SortKind::Stable).unwrap();
+        assert!(exclusive.sort(Axis(2), SortKind::Stable).is_err());
+
+        exclusive.partition(&[0], Axis(1)).unwrap();
+        assert_eq!(exclusive.get([1, 0]), Some(&4));
+    });
+}
+
+#[test]
+fn active_borrows_are_listed() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 10, false);
+        let data = array.data() as usize;
+
+        let find = || {
+            active_borrows(py)
+                .into_iter()
+                .find(|info| info.data_range().start == data)
+        };
+
+        assert!(find().is_none());
+
+        let shared1 = array.readonly();
+        let _shared2 = shared1.clone();
+
+        let info = find().unwrap();
+        assert_eq!(info.kind(), BorrowKind::Shared);
+        assert_eq!(info.count(), 2);
+        assert_eq!(info.shape(), [10]);
+        assert_eq!(info.data_range(), data..data + 80);
+    });
+}
+
+fn is_writeable<T, D>(array: &PyArray<T, D>) -> bool {
+    unsafe { (*array.as_array_ptr()).flags & NPY_ARRAY_WRITEABLE != 0 }
+}
+
+#[test]
+fn frozen_arrays_are_permanently_borrowed() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (2, 3), false);
+
+        let exclusive = array.readwrite();
+        let err = array.try_freeze().unwrap_err();
+        assert!(matches!(
+            err,
+            BorrowError::AlreadyBorrowed | BorrowError::AlreadyBorrowedBy(_)
+        ));
+        drop(exclusive);
+
+        let frozen = array.freeze();
+        assert_eq!(frozen.as_array(), array![[0.0, 0.0, 0.0], [0.0, 0.0, 0.0]]);
+        assert_eq!(frozen.get([1, 2]), Some(&0.0));
+        assert_eq!(frozen.get([2, 0]), None);
+        assert!(!is_writeable(array));
+
+        drop(frozen);
+
+        assert!(array.try_readwrite().is_err());
+        let row = array.get_item(0).unwrap();
+        assert!(row
+            .downcast::<PyArray1<f64>>()
+            .unwrap()
+            .try_readwrite()
+            .is_err());
+        assert!(array.try_readonly().is_ok());
+
+        let _frozen = array.freeze();
+    });
+}
+
+#[test]
+fn frozen_arrays_can_be_extracted() {
+    Python::with_gil(|py| {
+        let array = PyArray::<i32, _>::arange(py, 0, 10, 1);
+
+        let err = array.extract::<PyFrozenArray1<i32>>().unwrap_err();
+        assert!(err.to_string().contains("The given array is not frozen"));
+
+        array.freeze();
+
+        let frozen = array.extract::<PyFrozenArray1<i32>>().unwrap();
+        assert_eq!(frozen.as_slice().unwrap()[..3], [0, 1, 2]);
+        assert!(frozen.as_pyarray(py).is(array));
+
+        let sum = py.allow_threads(move || spawn(move || frozen.as_array().sum()).join().unwrap());
+        assert_eq!(sum, 45);
+    });
+}
+
+#[test]
+fn detached_shared_borrow_stays_active() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (2, 3), false);
+
+        let view = array.readonly().detach_view();
+
+        assert!(array.try_readwrite().is_err());
+        assert!(array.try_readonly().is_ok());
+
+        let sum = py.allow_threads(|| view.as_array().sum());
+        assert_eq!(sum, 0.0);
+        assert_eq!(view.as_slice().unwrap(), &[0.0; 6]);
+
+        drop(view);
+
+        assert!(array.try_readwrite().is_ok());
+    });
+}
+
+#[test]
+fn detached_exclusive_borrow_is_released_without_gil() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 10, false);
+
+        let mut view = array.readwrite().detach_view();
+
+        assert!(array.try_readonly().is_err());
+
+        py.allow_threads(move || {
+            spawn(move || {
+                view.as_array_mut().fill(1.0);
+                view.as_slice_mut().unwrap()[0] = 2.0;
+            })
+            .join()
+            .unwrap();
+        });
+
+        let shared = array.readonly();
+        assert_eq!(shared.as_slice().unwrap()[..3], [2.0, 1.0, 1.0]);
+    });
+}
+
+#[test]
+fn split_at_yields_independent_borrows() {
+    Python::with_gil(|py| {
+        let array = PyArray::from_owned_array(py, array![[1, 2, 3], [4, 5, 6]]);
+
+        let (mut top, bottom) = array.readwrite().split_at(Axis(0), 1);
+        assert_eq!(top.shape(), [1, 3]);
+        assert_eq!(bottom.shape(), [1, 3]);
+
+        top.as_array_mut().fill(0);
+        assert!(array.try_readonly().is_err());
+
+        let (left, right) = bottom.split_at(Axis(1), 3);
+        assert_eq!(left.shape(), [1, 3]);
+        assert_eq!(right.shape(), [1, 0]);
+
+        drop(left);
+        let row = |index: usize| {
+            array
+                .get_item(index)
+                .unwrap()
+                .downcast::<PyArray1<i32>>()
+                .unwrap()
+        };
+        assert!(row(1).try_readwrite().is_ok());
+        assert!(row(0).try_readonly().is_err());
+
+        let locals = [("top", top.to_object(py))].into_py_dict(py);
+        drop(top);
+        py_run!(py, *locals, "assert (top == 0).all()");
+
+        assert_eq!(array.readonly().as_array(), array![[0, 0, 0], [4, 5, 6]]);
+    });
+}
+
+#[test]
+#[should_panic]
+fn split_at_out_of_bounds() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (2, 3), false);
+
+        let _ = array.readwrite().split_at(Axis(1), 4);
+    });
+}
+
+#[test]
+fn axis_chunks_are_released_separately() {
+    Python::with_gil(|py| {
+        let array = PyArray::<i32, _>::zeros(py, (5, 2), false);
+
+        let mut chunks = array.readwrite().axis_chunks(Axis(0), 2);
+        assert_eq!(chunks.len(), 3);
+
+        let mut first = chunks.next().unwrap();
+        assert_eq!(first.shape(), [2, 2]);
+        assert_eq!(chunks.len(), 2);
+
+        let row = |index: usize| {
+            array
+                .get_item(index)
+                .unwrap()
+                .downcast::<PyArray1<i32>>()
+                .unwrap()
+        };
+
+        // The rest of the array stays borrowed by the iterator.
+        assert!(row(4).try_readonly().is_err());
+
+        first.as_array_mut().fill(1);
+        drop(first);
+        assert!(row(0).try_readonly().is_ok());
+
+        let rest = chunks.collect::<Vec<_>>();
+        assert_eq!(rest.len(), 2);
+        assert_eq!(rest[0].shape(), [2, 2]);
+        assert_eq!(rest[1].shape(), [1, 2]);
+        drop(rest);
+
+        assert_eq!(array.readwrite().axis_chunks(Axis(1), 3).count(), 1);
+
+        let empty = PyArray::<i32, _>::zeros(py, (0, 2), false);
+        assert_eq!(empty.readwrite().axis_chunks(Axis(0), 2).count(), 0);
+    });
+}
+
+#[test]
+fn shared_handles_outlive_gil_scope() {
+    let (array, shared) = Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (10, 10), false);
+        let shared = array.readonly().into_shared();
+
+        (array.to_owned(), shared)
+    });
+
+    let threads = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            spawn(move || shared.as_array().sum())
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 0.0);
+    }
+
+    Python::with_gil(|py| {
+        let array = array.as_ref(py);
+
+        assert!(array.try_readwrite().is_err());
+        assert!(array.try_readonly().is_ok());
+
+        let clone = shared.clone();
+        drop(shared);
+        assert!(array.try_readwrite().is_err());
+
+        assert_eq!(clone.as_slice().unwrap().len(), 100);
+        drop(clone);
+        assert!(array.try_readwrite().is_ok());
+    });
+}
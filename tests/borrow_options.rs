@@ -0,0 +1,149 @@
+use numpy::{
+    borrow::{set_diagnostics, set_strict, BorrowKind},
+    npyffi::NPY_ARRAY_WRITEABLE,
+    BorrowError, PyArray, PyArray1,
+};
+use pyo3::{types::IntoPyDict, Python};
+
+/// Enables a process-wide option and disables it again when dropped, even if the test fails.
+///
+/// These tests live in their own file so that they do not affect the other borrow tests running in parallel.
+/// As they still run in parallel with each other, no Python code may run while the option is enabled
+/// as the interpreter could hand over the GIL to another test.
+struct EnabledOption<'py> {
+    py: Python<'py>,
+    set: fn(Python, bool),
+}
+
+impl<'py> EnabledOption<'py> {
+    fn new(py: Python<'py>, set: fn(Python, bool)) -> Self {
+        set(py, true);
+
+        Self { py, set }
+    }
+}
+
+impl Drop for EnabledOption<'_> {
+    fn drop(&mut self) {
+        (self.set)(self.py, false);
+    }
+}
+
+#[test]
+fn diagnostics_describe_conflicting_borrow() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (2, 3), false);
+
+        let _diagnostics = EnabledOption::new(py, set_diagnostics);
+
+        let (_exclusive, line) = (array.readwrite(), line!());
+
+        let info = match array.try_readonly() {
+            Err(BorrowError::AlreadyBorrowedBy(info)) => info,
+            res => panic!("unexpected result: {:?}", res.map(|_| ())),
+        };
+
+        assert_eq!(info.kind(), BorrowKind::Exclusive);
+        assert_eq!(info.count(), 1);
+        assert_eq!(info.shape(), [2, 3]);
+        assert_eq!(info.strides(), [24, 8]);
+
+        let data = array.data() as usize;
+        assert_eq!(info.data_range(), data..data + 48);
+
+        let location = info.location().unwrap();
+        assert!(location.file().ends_with("borrow_options.rs"));
+        assert_eq!(location.line(), line);
+        let column = location.column();
+
+        let msg = BorrowError::AlreadyBorrowedBy(info).to_string();
+        assert!(msg.starts_with("The given array is already borrowed by exclusive borrow"));
+        assert!(msg.ends_with(&format!("borrow_options.rs:{}:{}", line, column)));
+    });
+}
+
+fn is_writeable<T, D>(array: &PyArray<T, D>) -> bool {
+    unsafe { (*array.as_array_ptr()).flags & NPY_ARRAY_WRITEABLE != 0 }
+}
+
+#[test]
+fn strict_mode_locks_shared_borrows() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 10, false);
+
+        let strict = EnabledOption::new(py, set_strict);
+
+        let shared1 = array.readonly();
+        let shared2 = array.readonly();
+        assert!(!is_writeable(array));
+
+        // Calling the method directly does not run Python code which could release the GIL.
+        let res = array.call_method1("__setitem__", (0, 1.0));
+        assert!(res.is_err());
+
+        drop(shared1);
+        assert!(!is_writeable(array));
+
+        drop(strict);
+
+        drop(shared2);
+        assert!(is_writeable(array));
+    });
+}
+
+#[test]
+fn strict_mode_toggled_while_borrowed() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, 10, false);
+
+        let shared1 = array.readonly();
+        assert!(is_writeable(array));
+
+        let strict = EnabledOption::new(py, set_strict);
+
+        let shared2 = array.readonly();
+        assert!(!is_writeable(array));
+
+        drop(strict);
+
+        drop(shared1);
+        assert!(!is_writeable(array));
+
+        drop(shared2);
+        assert!(is_writeable(array));
+    });
+}
+
+#[test]
+fn strict_mode_refuses_locked_arrays() {
+    Python::with_gil(|py| {
+        let array = PyArray::<f64, _>::zeros(py, (2, 3), false);
+        unsafe {
+            (*array.as_array_ptr()).flags &= !NPY_ARRAY_WRITEABLE;
+        }
+
+        let view = py
+            .eval(
+                "array[0].view()",
+                None,
+                Some([("array", array)].into_py_dict(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray1<f64>>()
+            .unwrap();
+        unsafe {
+            (*view.as_array_ptr()).flags |= NPY_ARRAY_WRITEABLE;
+        }
+
+        let strict = EnabledOption::new(py, set_strict);
+
+        let err = view.try_readwrite().unwrap_err();
+        assert!(matches!(err, BorrowError::NotWriteable));
+
+        assert!(view.try_readonly().is_ok());
+
+        drop(strict);
+
+        assert!(view.try_readwrite().is_ok());
+    });
+}
@@ -0,0 +1,322 @@
+#![cfg(feature = "derive")]
+
+use std::mem::size_of;
+
+use numpy::{dtype, get_array_module, Element, PyArray1, PyArrayDescr, PyUFunc};
+use pyo3::{
+    py_run,
+    types::{IntoPyDict, PyDict},
+    PyObject, Python,
+};
+
+fn get_np_locals(py: Python) -> &PyDict {
+    [("np", get_array_module(py).unwrap())].into_py_dict(py)
+}
+
+#[derive(Clone, Element)]
+#[repr(C)]
+struct Record {
+    x: f64,
+    y: f64,
+    id: u32,
+}
+
+#[derive(Clone, Element)]
+#[repr(C)]
+struct Pair(u8, f32);
+
+#[derive(Clone, Element)]
+#[repr(C)]
+struct Nested {
+    flag: bool,
+    record: Record,
+}
+
+#[derive(Clone, Copy, Element)]
+#[repr(C)]
+struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+#[derive(Clone, Element)]
+#[repr(C, packed)]
+struct Packed {
+    a: u8,
+    b: u32,
+}
+
+#[derive(Clone, Element)]
+#[repr(C)]
+struct WithObject {
+    value: f64,
+    object: PyObject,
+}
+
+#[derive(Clone, Element)]
+#[repr(C)]
+struct Particle {
+    position: [f32; 3],
+    mass: f32,
+}
+
+#[derive(Clone, Element)]
+#[repr(C)]
+struct Token {
+    r#type: u8,
+    value: i32,
+}
+
+#[test]
+fn record_dtype() {
+    Python::with_gil(|py| {
+        let dt = dtype::<Record>(py);
+
+        assert_eq!(dt.names(), Some(vec!["x", "y", "id"]));
+        assert_eq!(dt.itemsize(), size_of::<Record>());
+        assert!(dt.is_aligned_struct());
+
+        let (x, x_offset) = dt.get_field("x").unwrap();
+        assert!(x.is_equiv_to(dtype::<f64>(py)));
+        assert_eq!(x_offset, 0);
+
+        let (id, id_offset) = dt.get_field("id").unwrap();
+        assert!(id.is_equiv_to(dtype::<u32>(py)));
+        assert_eq!(id_offset, 16);
+
+        assert!(Record::IS_COPY);
+        assert!(dtype::<Record>(py).is(dt));
+    });
+}
+
+#[test]
+fn tuple_struct_dtype() {
+    Python::with_gil(|py| {
+        let dt = dtype::<Pair>(py);
+
+        assert_eq!(dt.names(), Some(vec!["f0", "f1"]));
+        assert_eq!(dt.get_field("f1").unwrap().1, 4);
+        assert_eq!(dt.itemsize(), 8);
+    });
+}
+
+#[test]
+fn raw_identifier_field_names() {
+    Python::with_gil(|py| {
+        let dt = dtype::<Token>(py);
+
+        assert_eq!(dt.names(), Some(vec!["type", "value"]));
+    });
+}
+
+#[test]
+fn nested_dtype() {
+    Python::with_gil(|py| {
+        let dt = dtype::<Nested>(py);
+
+        let (record, offset) = dt.get_field("record").unwrap();
+        assert!(record.is_equiv_to(dtype::<Record>(py)));
+        assert_eq!(offset, 8);
+        assert_eq!(dt.itemsize(), size_of::<Nested>());
+    });
+}
+
+#[test]
+fn packed_dtype() {
+    Python::with_gil(|py| {
+        let dt = dtype::<Packed>(py);
+
+        assert_eq!(dt.get_field("b").unwrap().1, 1);
+        assert_eq!(dt.itemsize(), 5);
+        assert!(!dt.is_aligned_struct());
+    });
+}
+
+#[test]
+fn subarray_field_dtype() {
+    Python::with_gil(|py| {
+        let dt = dtype::<Particle>(py);
+
+        let (position, offset) = dt.get_field("position").unwrap();
+        assert!(position.has_subarray());
+        assert_eq!(position.shape(), vec![3]);
+        assert_eq!(offset, 0);
+        assert_eq!(dt.get_field("mass").unwrap().1, 12);
+
+        let array = py
+            .eval(
+                "np.array([((1, 2, 3), 4)], dtype=np.dtype([('position', '<f4', (3,)), ('mass', '<f4')], align=True))",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray1<Particle>>()
+            .unwrap();
+
+        let array = array.readonly();
+        assert_eq!(array.get(0).unwrap().position, [1.0, 2.0, 3.0]);
+    });
+}
+
+#[test]
+fn object_field_is_not_copy() {
+    Python::with_gil(|py| {
+        assert!(!WithObject::IS_COPY);
+        assert!(dtype::<WithObject>(py).has_object());
+    });
+}
+
+#[test]
+fn extract_record_array() {
+    Python::with_gil(|py| {
+        let array = py
+            .eval(
+                "np.array([(1.0, 2.0, 3), (4.0, 5.0, 6)], dtype=np.dtype([('x', '<f8'), ('y', '<f8'), ('id', '<u4')], align=True))",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap()
+            .downcast::<PyArray1<Record>>()
+            .unwrap();
+
+        let array = array.readonly();
+        let record = array.get(1).unwrap();
+        assert_eq!(record.x, 4.0);
+        assert_eq!(record.y, 5.0);
+        assert_eq!(record.id, 6);
+    });
+}
+
+#[test]
+fn extract_rejects_packed_layout() {
+    Python::with_gil(|py| {
+        let array = py
+            .eval(
+                "np.zeros(2, dtype=[('x', '<f8'), ('y', '<f8'), ('id', '<u4')])",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap();
+
+        assert!(array.downcast::<PyArray1<Record>>().is_err());
+    });
+}
+
+#[test]
+fn extract_rejects_mismatched_fields() {
+    Python::with_gil(|py| {
+        let array = py
+            .eval(
+                "np.zeros(2, dtype=np.dtype([('y', '<f8'), ('x', '<f8'), ('id', '<u4')], align=True))",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap();
+
+        assert!(array.downcast::<PyArray1<Record>>().is_err());
+
+        let array = py
+            .eval(
+                "np.zeros(2, dtype=np.dtype([('x', '<f8'), ('y', '<f8'), ('id', '<i4')], align=True))",
+                None,
+                Some(get_np_locals(py)),
+            )
+            .unwrap();
+
+        assert!(array.downcast::<PyArray1<Record>>().is_err());
+    });
+}
+
+#[test]
+fn record_array_to_python() {
+    Python::with_gil(|py| {
+        let array = PyArray1::from_vec(
+            py,
+            vec![
+                Record {
+                    x: 1.0,
+                    y: 2.0,
+                    id: 3,
+                },
+                Record {
+                    x: 4.0,
+                    y: 5.0,
+                    id: 6,
+                },
+            ],
+        );
+
+        py_run!(
+            py,
+            array,
+            "assert array['id'].tolist() == [3, 6]\nassert array.dtype.isalignedstruct"
+        );
+    });
+}
+
+#[test]
+fn from_fields_checks_alignment() {
+    Python::with_gil(|py| {
+        let fields = [("a", dtype::<u8>(py), 0), ("b", dtype::<u32>(py), 1)];
+
+        assert!(PyArrayDescr::from_fields(py, &fields, 5, true).is_err());
+        assert!(PyArrayDescr::from_fields(py, &fields, 5, false).is_ok());
+    });
+}
+
+#[test]
+fn register_ufunc_loops_for_records() {
+    Python::with_gil(|py| {
+        // SAFETY: No other loops are registered for structured element types.
+        unsafe {
+            let add = PyUFunc::from_numpy(py, "add").unwrap();
+            add.register_loop(|a: Interval, b: Interval| Interval {
+                lo: a.lo + b.lo,
+                hi: a.hi + b.hi,
+            })
+            .unwrap();
+
+            let multiply = PyUFunc::from_numpy(py, "multiply").unwrap();
+            multiply
+                .register_loop(|a: Interval, factor: f64| Interval {
+                    lo: a.lo * factor,
+                    hi: a.hi * factor,
+                })
+                .unwrap();
+        }
+
+        let a = PyArray1::from_vec(
+            py,
+            vec![Interval { lo: 0.0, hi: 1.0 }, Interval { lo: 2.0, hi: 4.0 }],
+        );
+
+        let locals = get_np_locals(py);
+        locals.set_item("a", a).unwrap();
+
+        py_run!(
+            py,
+            *locals,
+            r#"
+b = np.add(a, a)
+assert b.dtype == a.dtype
+assert b["lo"].tolist() == [0.0, 4.0] and b["hi"].tolist() == [2.0, 8.0]
+
+c = np.multiply(a, np.array([2.0, 0.5]))
+assert c["lo"].tolist() == [0.0, 1.0] and c["hi"].tolist() == [2.0, 2.0]
+"#
+        );
+    });
+}
+
+#[test]
+fn register_ufunc_loops_checks_types() {
+    Python::with_gil(|py| {
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+
+        let err = unsafe { add.register_loop(|a: f64, b: f64| a - b) }.unwrap_err();
+        assert!(err.to_string().contains("user-defined or structured"));
+
+        let err = unsafe { add.register_loop(|a: Interval| a) }.unwrap_err();
+        assert!(err.to_string().contains("must take 2 inputs"));
+    });
+}
@@ -0,0 +1,132 @@
+use numpy::{
+    errstate::{check_fperr, clear_fperr, get_fperr, ErrState, FloatErrorMode},
+    get_array_module, Ix1, PyArray1, PyUFunc,
+};
+use pyo3::{
+    exceptions::PyFloatingPointError,
+    py_run,
+    types::{IntoPyDict, PyDict},
+    Python,
+};
+
+fn get_np_locals(py: Python) -> &PyDict {
+    [("np", get_array_module(py).unwrap())].into_py_dict(py)
+}
+
+fn zero(py: Python) -> f64 {
+    // Prevents the operations below from being evaluated at compile time.
+    py.eval("0.0", None, None).unwrap().extract().unwrap()
+}
+
+#[test]
+fn guard_restores_previous_state() {
+    Python::with_gil(|py| {
+        let locals = get_np_locals(py);
+
+        py_run!(py, *locals, "np.seterr(all='warn')");
+
+        {
+            let _guard = ErrState::new()
+                .divide(FloatErrorMode::Ignore)
+                .invalid(FloatErrorMode::Raise)
+                .enter(py)
+                .unwrap();
+
+            py_run!(
+                py,
+                *locals,
+                r#"
+err = np.geterr()
+assert err["divide"] == "ignore" and err["invalid"] == "raise"
+assert err["over"] == "warn" and err["under"] == "warn"
+"#
+            );
+
+            let _guard = ErrState::all(FloatErrorMode::Call).enter(py).unwrap();
+
+            py_run!(py, *locals, "assert set(np.geterr().values()) == {'call'}");
+        }
+
+        py_run!(py, *locals, "assert set(np.geterr().values()) == {'warn'}");
+    });
+}
+
+#[test]
+fn numpy_operations_use_error_state() {
+    Python::with_gil(|py| {
+        let divide = PyUFunc::from_numpy(py, "divide").unwrap();
+        let a = PyArray1::from_slice(py, &[1.0, 2.0]);
+        let b = PyArray1::from_slice(py, &[0.0, 1.0]);
+
+        {
+            let _guard = ErrState::all(FloatErrorMode::Raise).enter(py).unwrap();
+
+            let err = divide.call::<_, f64, Ix1>((a, b)).unwrap_err();
+            assert!(err.is_instance_of::<PyFloatingPointError>(py));
+        }
+
+        let _guard = ErrState::all(FloatErrorMode::Ignore).enter(py).unwrap();
+
+        let c: &PyArray1<f64> = divide.call((a, b)).unwrap();
+        assert_eq!(c.readonly().as_slice().unwrap(), [f64::INFINITY, 2.0]);
+    });
+}
+
+#[test]
+fn get_fperr_reports_and_clears_flags() {
+    Python::with_gil(|py| {
+        let zero = zero(py);
+
+        clear_fperr(py);
+        assert!(get_fperr(py).is_empty());
+
+        assert_eq!(1.0 / zero, f64::INFINITY);
+        let errors = get_fperr(py);
+        assert!(errors.divide());
+        assert!(!errors.invalid());
+
+        assert!((zero / zero).is_nan());
+        let errors = get_fperr(py);
+        assert!(errors.invalid());
+        assert!(!errors.divide());
+
+        assert!((f64::MAX * (2.0 + zero)).is_infinite());
+        assert!(get_fperr(py).over());
+
+        assert!(get_fperr(py).is_empty());
+    });
+}
+
+#[test]
+fn check_fperr_follows_error_state() {
+    Python::with_gil(|py| {
+        let zero = zero(py);
+
+        {
+            let _guard = ErrState::new()
+                .divide(FloatErrorMode::Raise)
+                .enter(py)
+                .unwrap();
+
+            clear_fperr(py);
+            assert_eq!(1.0 / zero, f64::INFINITY);
+
+            let err = check_fperr(py, "reciprocal").unwrap_err();
+            assert!(err.is_instance_of::<PyFloatingPointError>(py));
+            assert!(err.to_string().contains("divide by zero"));
+
+            assert!(check_fperr(py, "reciprocal").is_ok());
+        }
+
+        let _guard = ErrState::new()
+            .divide(FloatErrorMode::Ignore)
+            .enter(py)
+            .unwrap();
+
+        clear_fperr(py);
+        assert_eq!(1.0 / zero, f64::INFINITY);
+
+        assert!(check_fperr(py, "reciprocal").is_ok());
+        assert!(get_fperr(py).is_empty());
+    });
+}
@@ -0,0 +1,450 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use ndarray::{
+    array, ArrayView1, ArrayView2, ArrayViewD, ArrayViewMut0, ArrayViewMut1, ArrayViewMut2, Axis,
+    Ix1,
+};
+use numpy::{
+    get_array_module, IntoPyArray, PyArray, PyArray0, PyArray1, PyArray2, PyArrayDyn,
+    PyGUFuncBuilder, PyUFunc, PyUFuncBuilder,
+};
+use pyo3::{
+    py_run,
+    types::{IntoPyDict, PyDict},
+    Python,
+};
+
+fn get_np_locals(py: Python) -> &PyDict {
+    [("np", get_array_module(py).unwrap())].into_py_dict(py)
+}
+
+#[test]
+fn unary_ufunc_broadcasts_and_writes_out() {
+    Python::with_gil(|py| {
+        let square = PyUFuncBuilder::new(py, "square")
+            .add_loop(|x: f64| x * x)
+            .build()
+            .unwrap();
+
+        py_run!(
+            py,
+            square,
+            r#"
+import numpy as np
+
+assert square.__name__ == "square"
+assert square.nin == 1 and square.nout == 1
+
+assert square(3.0) == 9.0
+assert square(np.arange(4.0).reshape(2, 2)).tolist() == [[0.0, 1.0], [4.0, 9.0]]
+
+out = np.full(3, -1.0)
+square(np.array([1.0, 2.0, 3.0]), out=out, where=[True, False, True])
+assert out.tolist() == [1.0, -1.0, 9.0]
+
+x = np.arange(6.0)[::2]
+assert square(x).tolist() == [0.0, 4.0, 16.0]
+"#
+        );
+    });
+}
+
+#[test]
+fn loops_are_selected_by_element_type() {
+    Python::with_gil(|py| {
+        let halve = PyUFuncBuilder::new(py, "halve")
+            .doc("Halves its argument.")
+            .add_loop(|x: i32| x / 2)
+            .add_loop(|x: f64| x / 2.0)
+            .build()
+            .unwrap();
+
+        let locals = get_np_locals(py);
+        locals.set_item("halve", halve).unwrap();
+
+        py_run!(
+            py,
+            *locals,
+            r#"
+assert halve.types == ["i->i", "d->d"]
+assert halve.__doc__.endswith("Halves its argument.")
+
+res = halve(np.array([3, 4], dtype=np.int16))
+assert res.dtype == np.int32 and res.tolist() == [1, 2]
+
+res = halve(np.array([3.0, 4.0], dtype=np.float32))
+assert res.dtype == np.float64 and res.tolist() == [1.5, 2.0]
+
+try:
+    halve(np.array(["a"]))
+except TypeError:
+    pass
+else:
+    assert False
+"#
+        );
+    });
+}
+
+#[test]
+fn binary_ufunc_broadcasts_mixed_types() {
+    Python::with_gil(|py| {
+        let scale = PyUFuncBuilder::new(py, "scale")
+            .add_loop(|x: f64, factor: u8| x * factor as f64)
+            .build()
+            .unwrap();
+
+        let locals = get_np_locals(py);
+        locals.set_item("scale", scale).unwrap();
+
+        py_run!(
+            py,
+            *locals,
+            r#"
+res = scale(np.ones((2, 3)), np.array([1, 2, 3], dtype=np.uint8))
+assert res.shape == (2, 3) and res[1].tolist() == [1.0, 2.0, 3.0]
+"#
+        );
+    });
+}
+
+#[test]
+fn ufunc_keeps_captured_state_alive() {
+    Python::with_gil(|py| {
+        let calls = Arc::new(AtomicUsize::new(0));
+
+        let count = {
+            let calls = calls.clone();
+
+            PyUFuncBuilder::new(py, "count")
+                .add_loop(move |x: i64| {
+                    calls.fetch_add(1, Ordering::Relaxed);
+                    x
+                })
+                .build()
+                .unwrap()
+        };
+
+        assert_eq!(Arc::strong_count(&calls), 2);
+
+        py_run!(py, count, "count(list(range(5)))");
+        assert_eq!(calls.load(Ordering::Relaxed), 5);
+    });
+}
+
+#[test]
+fn invalid_loops_fail_to_build() {
+    Python::with_gil(|py| {
+        assert!(PyUFuncBuilder::new(py, "empty").build().is_err());
+
+        let err = PyUFuncBuilder::new(py, "mixed")
+            .add_loop(|x: f64| x)
+            .add_loop(|x: f64, y: f64| x + y)
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("must take 1 inputs"));
+
+        let err = PyUFuncBuilder::new(py, "subarray")
+            .add_loop(|x: [f64; 2]| x[0])
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("only support"));
+
+        assert!(PyUFuncBuilder::new(py, "nul\0")
+            .add_loop(|x: f64| x)
+            .build()
+            .is_err());
+    });
+}
+
+#[test]
+fn gufunc_broadcasts_outer_dimensions() {
+    Python::with_gil(|py| {
+        let inner = PyGUFuncBuilder::new(py, "inner", "(n),(n)->()")
+            .add_loop(
+                |a: ArrayView1<i64>, b: ArrayView1<i64>, mut out: ArrayViewMut0<i64>| {
+                    out[()] = a.dot(&b);
+                },
+            )
+            .add_loop(
+                |a: ArrayView1<f64>, b: ArrayView1<f64>, mut out: ArrayViewMut0<f64>| {
+                    out[()] = a.dot(&b);
+                },
+            )
+            .build()
+            .unwrap();
+
+        let locals = get_np_locals(py);
+        locals.set_item("inner", inner).unwrap();
+
+        py_run!(
+            py,
+            *locals,
+            r#"
+assert inner.signature == "(n),(n)->()"
+assert inner.types == ["ll->l", "dd->d"]
+
+a = np.arange(6).reshape(2, 3)
+assert inner(a, np.ones(3, dtype=np.int64)).tolist() == [3, 12]
+
+a = np.arange(24.0).reshape(2, 3, 4)
+b = np.arange(4.0)[::-1]
+assert np.array_equal(inner(a, b), np.einsum("ijk,k->ij", a, b))
+assert np.array_equal(inner(a[..., ::2], b[::2]), np.einsum("ijk,k->ij", a[..., ::2], b[::2]))
+
+try:
+    inner(np.ones(3), np.ones(4))
+except ValueError:
+    pass
+else:
+    assert False
+"#
+        );
+    });
+}
+
+#[test]
+fn gufunc_with_shared_and_new_core_dimensions() {
+    Python::with_gil(|py| {
+        let matmul = PyGUFuncBuilder::new(py, "matmul", "(n,k),(k,m)->(n,m)")
+            .add_loop(
+                |a: ArrayView2<f64>, b: ArrayView2<f64>, mut c: ArrayViewMut2<f64>| {
+                    c.assign(&a.dot(&b));
+                },
+            )
+            .build()
+            .unwrap();
+
+        let total = PyGUFuncBuilder::new(py, "total", "(i,j)->()")
+            .add_loop(|a: ArrayViewD<f64>, mut out: ArrayViewMut0<f64>| {
+                out[()] = a.sum();
+            })
+            .build()
+            .unwrap();
+
+        let locals = get_np_locals(py);
+        locals.set_item("matmul", matmul).unwrap();
+        locals.set_item("total", total).unwrap();
+
+        py_run!(
+            py,
+            *locals,
+            r#"
+a = np.arange(24.0).reshape(2, 3, 4)
+b = np.arange(8.0).reshape(4, 2)
+assert np.array_equal(matmul(a, b), a @ b)
+assert np.array_equal(matmul(a.transpose(0, 2, 1), a), a.transpose(0, 2, 1) @ a)
+
+assert total(np.ones((5, 2, 3))).tolist() == [6.0] * 5
+"#
+        );
+    });
+}
+
+#[test]
+fn gufunc_panics_are_raised_as_exceptions() {
+    Python::with_gil(|py| {
+        let first = PyGUFuncBuilder::new(py, "first", "(n)->()")
+            .add_loop(|a: ArrayView1<f64>, mut out: ArrayViewMut0<f64>| {
+                out[()] = a[0];
+            })
+            .build()
+            .unwrap();
+
+        py_run!(
+            py,
+            first,
+            r#"
+import numpy as np
+
+assert first(np.arange(6.0).reshape(2, 3)).tolist() == [0.0, 3.0]
+
+try:
+    first(np.ones((2, 0)))
+except BaseException as err:
+    assert "out of bounds" in str(err)
+else:
+    assert False
+"#
+        );
+    });
+}
+
+#[test]
+fn invalid_gufuncs_fail_to_build() {
+    Python::with_gil(|py| {
+        let err = PyGUFuncBuilder::new(py, "invalid", "(n),(n)")
+            .add_loop(|_: ArrayView1<f64>, _: ArrayViewMut1<f64>| {})
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("invalid gufunc signature"));
+
+        let err = PyGUFuncBuilder::new(py, "two_outputs", "(n)->(),()")
+            .add_loop(|_: ArrayView1<f64>, _: ArrayViewMut0<f64>| {})
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("single output"));
+
+        let err = PyGUFuncBuilder::new(py, "mismatch", "(n,m)->(n)")
+            .add_loop(|_: ArrayView1<f64>, _: ArrayViewMut1<f64>| {})
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("do not match signature"));
+
+        let err = PyGUFuncBuilder::new(py, "arity", "(n),(n)->()")
+            .add_loop(|_: ArrayView1<f64>, _: ArrayViewMut0<f64>| {})
+            .build()
+            .unwrap_err();
+        assert!(err.to_string().contains("must take 2 inputs"));
+
+        assert!(PyGUFuncBuilder::new(py, "empty", "(n)->()")
+            .build()
+            .is_err());
+    });
+}
+
+#[test]
+fn ufuncs_can_be_looked_up_and_extracted() {
+    Python::with_gil(|py| {
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+        assert_eq!(add.nin(), 2);
+        assert_eq!(add.nout(), 1);
+
+        let divmod = PyUFunc::from_numpy(py, "divmod").unwrap();
+        assert_eq!(divmod.nout(), 2);
+
+        assert!(PyUFunc::from_numpy(py, "ndarray").is_err());
+        assert!(PyUFunc::from_numpy(py, "does_not_exist").is_err());
+
+        let locals = get_np_locals(py);
+        let logaddexp = py.eval("np.logaddexp", None, Some(locals)).unwrap();
+        assert!(logaddexp.extract::<&PyUFunc>().is_ok());
+
+        let square = PyUFuncBuilder::new(py, "square")
+            .add_loop(|x: f64| x * x)
+            .build()
+            .unwrap();
+        assert_eq!(square.nin(), 1);
+    });
+}
+
+#[test]
+fn call_selects_loop_for_result_type() {
+    Python::with_gil(|py| {
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+
+        let a = PyArray::from_slice(py, &[1_i32, 2, 3]);
+        let b = array![[10_i32], [20]].into_pyarray(py);
+
+        let c: &PyArray2<i64> = add.call((a, b)).unwrap();
+        assert_eq!(c.readonly().as_array(), array![[11, 12, 13], [21, 22, 23]]);
+
+        let c: &PyArray1<f64> = add.call((a, 0.5)).unwrap();
+        assert_eq!(c.readonly().as_slice().unwrap(), [1.5, 2.5, 3.5]);
+
+        let c = add.call::<_, f64, Ix1>((a, b));
+        assert!(c.is_err());
+
+        let a = PyArray::from_slice(py, &[1.5_f64]);
+        assert!(add.call::<_, i32, Ix1>((a, a)).is_err());
+    });
+}
+
+#[test]
+fn call_out_writes_into_borrowed_array() {
+    Python::with_gil(|py| {
+        let maximum = PyUFunc::from_numpy(py, "maximum").unwrap();
+
+        let a = PyArray::from_slice(py, &[1.0, 5.0, 3.0]);
+        let out = PyArray::<f64, _>::zeros(py, [2, 3], false);
+
+        let mut out_rw = out.readwrite();
+        maximum.call_out((a, 2.0), &mut out_rw).unwrap();
+        assert_eq!(out_rw.as_array(), array![[2.0, 5.0, 3.0], [2.0, 5.0, 3.0]]);
+
+        let out = PyArray::<f64, _>::zeros(py, 2, false);
+        assert!(maximum.call_out((a, 2.0), &mut out.readwrite()).is_err());
+    });
+}
+
+#[test]
+fn reduce_and_accumulate_along_axis() {
+    Python::with_gil(|py| {
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+        let maximum = PyUFunc::from_numpy(py, "maximum").unwrap();
+
+        let a = array![[1_u8, 7, 3], [4, 2, 6]].into_pyarray(py);
+
+        let sum: &PyArray1<u64> = add.reduce(a, Axis(0)).unwrap();
+        assert_eq!(sum.readonly().as_slice().unwrap(), [5, 9, 9]);
+
+        let max: &PyArray1<u8> = maximum.reduce(a, Axis(1)).unwrap();
+        assert_eq!(max.readonly().as_slice().unwrap(), [7, 6]);
+
+        let total: &PyArray0<f64> = add.reduce(sum, Axis(0)).unwrap();
+        assert_eq!(total.readonly().as_array()[()], 23.0);
+
+        let running: &PyArray2<i64> = add.accumulate(a, Axis(1)).unwrap();
+        assert_eq!(
+            running.readonly().as_array(),
+            array![[1, 8, 11], [4, 6, 12]]
+        );
+
+        let empty = PyArray::<f64, _>::zeros(py, 0, false);
+        assert!(maximum.reduce::<f64, _, _>(empty, Axis(0)).is_err());
+    });
+}
+
+#[test]
+#[should_panic(expected = "axis 2 is out of bounds for array of dimension 2")]
+fn reduce_along_invalid_axis() {
+    Python::with_gil(|py| {
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+        let a = PyArray::<f64, _>::zeros(py, [2, 3], false);
+
+        let _ = add.reduce::<f64, _, _>(a, Axis(2));
+    });
+}
+
+#[test]
+fn reduceat_and_outer() {
+    Python::with_gil(|py| {
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+        let multiply = PyUFunc::from_numpy(py, "multiply").unwrap();
+
+        let a = PyArray::arange(py, 0, 8, 1);
+
+        let sums: &PyArray1<i64> = add.reduceat(a, &[0, 4, 1, 5], Axis(0)).unwrap();
+        assert_eq!(sums.readonly().as_slice().unwrap(), [6, 4, 10, 18]);
+
+        let b = PyArray::from_slice(py, &[1.0, 2.0]);
+        let c = array![[1.0, 10.0], [100.0, 1000.0]].into_pyarray(py);
+
+        let prod: &PyArrayDyn<f64> = multiply.outer(b, c).unwrap();
+        assert_eq!(prod.shape(), [2, 2, 2]);
+        assert_eq!(
+            prod.readonly().as_slice().unwrap(),
+            [1.0, 10.0, 100.0, 1000.0, 2.0, 20.0, 200.0, 2000.0]
+        );
+    });
+}
+
+#[test]
+fn at_applies_repeated_indices() {
+    Python::with_gil(|py| {
+        let negative = PyUFunc::from_numpy(py, "negative").unwrap();
+        let add = PyUFunc::from_numpy(py, "add").unwrap();
+
+        let a = array![[1, 2], [3, 4], [5, 6]].into_pyarray(py);
+
+        negative.at(&mut a.readwrite(), &[0, 2, 2]).unwrap();
+        assert_eq!(a.readonly().as_array(), array![[-1, -2], [3, 4], [5, 6]]);
+
+        let b = PyArray::from_slice(py, &[10, 100]);
+        add.at_with(&mut a.readwrite(), &[1, 1], b).unwrap();
+        assert_eq!(a.readonly().as_array(), array![[-1, -2], [23, 204], [5, 6]]);
+    });
+}